@@ -0,0 +1,34 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use rust_toolchain_file::toml::Parser;
+use rust_toolchain_file::RustToolchainToml;
+
+const RUSTUP_BOOK_SPEC: &str =
+    include_str!("../tests/fixtures/rustup-book-layout/rust-toolchain.toml");
+
+const RUSTUP_BOOK_LOCAL_TOOLCHAIN: &str =
+    include_str!("../tests/fixtures/rustup-book-local-toolchain/rust-toolchain.toml");
+
+fn parse(c: &mut Criterion) {
+    for (name, content) in [
+        ("spec", RUSTUP_BOOK_SPEC),
+        ("path", RUSTUP_BOOK_LOCAL_TOOLCHAIN),
+    ] {
+        let mut group = c.benchmark_group(name);
+
+        group.bench_function("toml_edit_from_slice", |b| {
+            b.iter(|| {
+                toml_edit::de::from_slice::<RustToolchainToml>(black_box(content.as_bytes()))
+                    .unwrap()
+            })
+        });
+
+        group.bench_function("parse", |b| {
+            b.iter(|| Parser::new(black_box(content)).parse().unwrap())
+        });
+
+        group.finish();
+    }
+}
+
+criterion_group!(benches, parse);
+criterion_main!(benches);
@@ -0,0 +1,96 @@
+//! Accessors which do not depend on the variant of a toolchain file.
+
+use crate::legacy::LegacyChannel;
+use crate::toml::{Component, Profile, Target, ToolchainSection, ToolchainSpec};
+use crate::{PortablePath, ToolchainFile, Variant};
+
+#[cfg(test)]
+mod tests;
+
+/// Whether a toolchain file selects a toolchain by its name, or by the path to a local toolchain.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolchainKind {
+    /// A channel, optionally with components, targets and a profile.
+    Spec,
+    /// The path to a local toolchain.
+    Path,
+}
+
+impl ToolchainFile {
+    /// The format in which this file is written.
+    pub fn variant(&self) -> Variant {
+        match self {
+            Self::Legacy(_) => Variant::Legacy,
+            Self::Toml(_) => Variant::Toml,
+        }
+    }
+
+    /// Whether this file selects a toolchain by its name, or by its path.
+    pub fn kind(&self) -> ToolchainKind {
+        match self.path() {
+            Some(_) => ToolchainKind::Path,
+            None => ToolchainKind::Spec,
+        }
+    }
+
+    /// The name of the selected toolchain, unless the file selects a toolchain by its path.
+    pub fn channel(&self) -> Option<&str> {
+        match self {
+            Self::Legacy(file) => file.spec(),
+            Self::Toml(file) => file.toolchain().spec()?.channel().map(|c| c.name()),
+        }
+    }
+
+    /// The path to the selected toolchain, as written, for either variant.
+    ///
+    /// Use [`PortablePath::as_path`] or [`PortablePath::as_utf8_path`] for a platform path.
+    pub fn path(&self) -> Option<&PortablePath> {
+        match self {
+            Self::Legacy(file) => file.portable_path(),
+            Self::Toml(file) => file.toolchain().path().map(|p| p.portable_path()),
+        }
+    }
+
+    /// The components, if specified. A legacy file can not specify components.
+    pub fn components(&self) -> Option<&[Component]> {
+        self.toml_spec()?.components()
+    }
+
+    /// The targets, if specified. A legacy file can not specify targets.
+    pub fn targets(&self) -> Option<&[Target]> {
+        self.toml_spec()?.targets()
+    }
+
+    /// The profile, if specified. A legacy file can not specify a profile.
+    pub fn profile(&self) -> Option<&Profile> {
+        self.toml_spec()?.profile()
+    }
+
+    fn toml_spec(&self) -> Option<&ToolchainSpec> {
+        match self {
+            Self::Legacy(_) => None,
+            Self::Toml(file) => file.toolchain().spec(),
+        }
+    }
+}
+
+impl LegacyChannel {
+    /// Whether this channel is a toolchain name, or the path to a local toolchain.
+    pub fn kind(&self) -> ToolchainKind {
+        match self {
+            Self::Spec(_) => ToolchainKind::Spec,
+            Self::Path(_) => ToolchainKind::Path,
+        }
+    }
+}
+
+impl ToolchainSection {
+    /// Whether this section specifies a toolchain by its name, or by the path to a local toolchain.
+    pub fn kind(&self) -> ToolchainKind {
+        match self {
+            Self::Spec(_) => ToolchainKind::Spec,
+            Self::Path(_) => ToolchainKind::Path,
+        }
+    }
+}
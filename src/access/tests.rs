@@ -0,0 +1,68 @@
+use crate::test_helpers::{legacy, toml};
+use crate::{PortablePath, RustToolchainToml, ToolchainFile, ToolchainKind, Variant};
+use yare::parameterized;
+
+#[parameterized(
+    legacy_spec = { legacy("stable"), Variant::Legacy, ToolchainKind::Spec },
+    legacy_path = { legacy("/opt/rust"), Variant::Legacy, ToolchainKind::Path },
+    toml_spec = { toml(RustToolchainToml::builder().with_channel("stable")), Variant::Toml, ToolchainKind::Spec },
+    toml_path = { toml(RustToolchainToml::builder().with_path("/opt/rust")), Variant::Toml, ToolchainKind::Path },
+)]
+fn variant_and_kind(file: ToolchainFile, variant: Variant, kind: ToolchainKind) {
+    assert_eq!(file.variant(), variant);
+    assert_eq!(file.kind(), kind);
+}
+
+#[parameterized(
+    legacy_file = { legacy("nightly-2020-07-10") },
+    toml_file = { toml(RustToolchainToml::builder().with_channel("nightly-2020-07-10")) },
+)]
+fn channel(file: ToolchainFile) {
+    assert_eq!(file.channel(), Some("nightly-2020-07-10"));
+    assert!(file.path().is_none());
+}
+
+#[parameterized(
+    legacy_file = { legacy("/opt/rust") },
+    toml_file = { toml(RustToolchainToml::builder().with_path("/opt/rust")) },
+)]
+fn path(file: ToolchainFile) {
+    assert_eq!(file.path(), Some(&PortablePath::from("/opt/rust")));
+    assert!(file.channel().is_none());
+    assert!(file.components().is_none());
+}
+
+#[test]
+fn spec_keys() {
+    let file = toml(
+        RustToolchainToml::builder()
+            .with_channel("stable")
+            .with_components(["rustfmt", "clippy"])
+            .with_target("wasm32-unknown-unknown")
+            .with_profile("minimal"),
+    );
+
+    let names = |items: Option<Vec<&str>>| items.unwrap_or_default().join(",");
+
+    assert_eq!(
+        names(
+            file.components()
+                .map(|c| c.iter().map(|c| c.name()).collect())
+        ),
+        "rustfmt,clippy"
+    );
+    assert_eq!(
+        names(file.targets().map(|t| t.iter().map(|t| t.name()).collect())),
+        "wasm32-unknown-unknown"
+    );
+    assert_eq!(file.profile().map(|p| p.name()), Some("minimal"));
+}
+
+#[test]
+fn legacy_has_no_spec_keys() {
+    let file = legacy("stable");
+
+    assert!(file.components().is_none());
+    assert!(file.targets().is_none());
+    assert!(file.profile().is_none());
+}
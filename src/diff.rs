@@ -0,0 +1,324 @@
+//! A structured description of the changes between two toolchain files, for example to summarize a
+//! change in code review.
+
+use crate::toml::{release, Channel, Component, Profile, Target, ToolchainSection, ToolchainSpec};
+use crate::{PortablePath, RustToolchainToml, ToolchainFile, Variant};
+#[cfg(feature = "json")]
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::fmt;
+
+#[cfg(test)]
+mod tests;
+
+impl ToolchainFile {
+    /// Describe the changes from this toolchain file to the other.
+    ///
+    /// Components and targets are normalized before they are compared (see
+    /// [`ToolchainSpec::normalize`]), so different spellings or orders of them are not reported as
+    /// a change. The channel is compared as written, since a version without a patch version, like
+    /// `1.72`, selects the latest patch release rather than `1.72.0`.
+    pub fn diff(&self, other: &Self) -> Diff {
+        let mut changes = Vec::new();
+
+        let (from, to) = (self.variant(), other.variant());
+
+        if from != to {
+            changes.push(Change::Variant { from, to });
+        }
+
+        let from = RustToolchainToml::from(self.clone()).normalize_except_channel();
+        let to = RustToolchainToml::from(other.clone()).normalize_except_channel();
+
+        let empty = ToolchainSpec::empty();
+
+        match (from.toolchain(), to.toolchain()) {
+            (ToolchainSection::Path(from), ToolchainSection::Path(to)) => {
+                if from != to {
+                    changes.push(Change::Path {
+                        from: from.portable_path().clone(),
+                        to: to.portable_path().clone(),
+                    });
+                }
+            }
+            (ToolchainSection::Path(from), ToolchainSection::Spec(to)) => {
+                changes.push(Change::PathToSpec {
+                    from: from.portable_path().clone(),
+                });
+                diff_spec(&empty, to, &mut changes);
+            }
+            (ToolchainSection::Spec(from), ToolchainSection::Path(to)) => {
+                changes.push(Change::SpecToPath {
+                    to: to.portable_path().clone(),
+                });
+                diff_spec(from, &empty, &mut changes);
+            }
+            (ToolchainSection::Spec(from), ToolchainSection::Spec(to)) => {
+                diff_spec(from, to, &mut changes);
+            }
+        }
+
+        Diff { changes }
+    }
+}
+
+fn diff_spec(from: &ToolchainSpec, to: &ToolchainSpec, changes: &mut Vec<Change>) {
+    if from.channel() != to.channel() {
+        let names = from
+            .channel()
+            .map(Channel::name)
+            .zip(to.channel().map(Channel::name));
+
+        let direction = names.and_then(|(from, to)| match release::order(from, to)? {
+            Ordering::Less => Some(Direction::Upgrade),
+            Ordering::Greater => Some(Direction::Downgrade),
+            Ordering::Equal => None,
+        });
+
+        changes.push(Change::Channel {
+            from: from.channel().cloned(),
+            to: to.channel().cloned(),
+            direction,
+            releases: names.and_then(|(from, to)| release::minor_releases_between(from, to)),
+        });
+    }
+
+    let (from_components, to_components) = (
+        from.components().unwrap_or_default(),
+        to.components().unwrap_or_default(),
+    );
+
+    for component in from_components
+        .iter()
+        .filter(|c| !to_components.contains(c))
+    {
+        changes.push(Change::ComponentRemoved(component.clone()));
+    }
+
+    for component in to_components
+        .iter()
+        .filter(|c| !from_components.contains(c))
+    {
+        changes.push(Change::ComponentAdded(component.clone()));
+    }
+
+    let (from_targets, to_targets) = (
+        from.targets().unwrap_or_default(),
+        to.targets().unwrap_or_default(),
+    );
+
+    for target in from_targets.iter().filter(|t| !to_targets.contains(t)) {
+        changes.push(Change::TargetRemoved(target.clone()));
+    }
+
+    for target in to_targets.iter().filter(|t| !from_targets.contains(t)) {
+        changes.push(Change::TargetAdded(target.clone()));
+    }
+
+    if from.profile() != to.profile() {
+        changes.push(Change::Profile {
+            from: from.profile().cloned(),
+            to: to.profile().cloned(),
+        });
+    }
+}
+
+/// The changes between two toolchain files, see [`ToolchainFile::diff`].
+///
+/// The [`Display`](fmt::Display) implementation renders one change per line, while `Diff::to_json`
+/// renders a JSON array of changes, when the `json` feature is enabled.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(transparent)]
+pub struct Diff {
+    changes: Vec<Change>,
+}
+
+impl Diff {
+    pub fn changes(&self) -> &[Change] {
+        &self.changes
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Render the changes as a JSON array, in the representation of the [`schema`](crate::schema).
+    ///
+    /// For example, an upgraded channel is rendered as
+    /// `{"kind":"channel","value":{"from":"1.70.0","to":"1.72.1","direction":"upgrade","releases":2}}`.
+    #[cfg(feature = "json")]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("a diff can always be serialized to JSON")
+    }
+}
+
+impl fmt::Display for Diff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for change in &self.changes {
+            writeln!(f, "{change}")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "kind", content = "value", rename_all = "snake_case")]
+pub enum Change {
+    /// The file switched between the legacy and the TOML variant.
+    Variant {
+        from: Variant,
+        to: Variant,
+    },
+    /// A toolchain path was replaced by a toolchain specification.
+    PathToSpec {
+        from: PortablePath,
+    },
+    /// A toolchain specification was replaced by a toolchain path.
+    SpecToPath {
+        to: PortablePath,
+    },
+    Path {
+        from: PortablePath,
+        to: PortablePath,
+    },
+    /// The channel was set, removed or changed. When both channels are releases which can be
+    /// ordered, the direction is known, and when both are versions, the number of minor releases
+    /// between them is known as well.
+    Channel {
+        from: Option<Channel>,
+        to: Option<Channel>,
+        direction: Option<Direction>,
+        releases: Option<u64>,
+    },
+    ComponentAdded(Component),
+    ComponentRemoved(Component),
+    TargetAdded(Target),
+    TargetRemoved(Target),
+    Profile {
+        from: Option<Profile>,
+        to: Option<Profile>,
+    },
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Variant { from, to } => write!(
+                f,
+                "switched from a {} to a {} toolchain file",
+                variant_name(*from),
+                variant_name(*to)
+            ),
+            Self::PathToSpec { from } => {
+                write!(
+                    f,
+                    "replaced toolchain path '{from}' by a toolchain specification"
+                )
+            }
+            Self::SpecToPath { to } => {
+                write!(
+                    f,
+                    "replaced the toolchain specification by toolchain path '{to}'"
+                )
+            }
+            Self::Path { from, to } => write!(f, "changed path from '{from}' to '{to}'"),
+            Self::Channel {
+                from: Some(from),
+                to: Some(to),
+                direction,
+                releases,
+            } => {
+                write!(
+                    f,
+                    "changed channel from '{}' to '{}'",
+                    from.name(),
+                    to.name()
+                )?;
+
+                match (direction, releases) {
+                    (Some(direction), Some(1)) => write!(f, " ({direction} by 1 release)"),
+                    (Some(direction), Some(releases)) if *releases > 0 => {
+                        write!(f, " ({direction} by {releases} releases)")
+                    }
+                    (Some(direction), _) => write!(f, " ({direction})"),
+                    (None, _) => Ok(()),
+                }
+            }
+            Self::Channel {
+                from: None,
+                to: Some(to),
+                ..
+            } => {
+                write!(f, "set channel to '{}'", to.name())
+            }
+            Self::Channel {
+                from: Some(from),
+                to: None,
+                ..
+            } => {
+                write!(f, "removed channel '{}'", from.name())
+            }
+            Self::Channel { .. } => f.write_str("changed channel"),
+            Self::ComponentAdded(component) => write!(f, "added component '{}'", component.name()),
+            Self::ComponentRemoved(component) => {
+                write!(f, "removed component '{}'", component.name())
+            }
+            Self::TargetAdded(target) => write!(f, "added target '{}'", target.name()),
+            Self::TargetRemoved(target) => write!(f, "removed target '{}'", target.name()),
+            Self::Profile {
+                from: Some(from),
+                to: Some(to),
+            } => write!(
+                f,
+                "changed profile from '{}' to '{}'",
+                from.name(),
+                to.name()
+            ),
+            Self::Profile {
+                from: None,
+                to: Some(to),
+            } => {
+                write!(f, "set profile to '{}'", to.name())
+            }
+            Self::Profile {
+                from: Some(from),
+                to: None,
+            } => {
+                write!(f, "removed profile '{}'", from.name())
+            }
+            Self::Profile { .. } => f.write_str("changed profile"),
+        }
+    }
+}
+
+/// The direction in which a channel moved between two releases.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Direction {
+    Upgrade,
+    Downgrade,
+}
+
+impl Direction {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Upgrade => "upgrade",
+            Self::Downgrade => "downgrade",
+        }
+    }
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+fn variant_name(variant: Variant) -> &'static str {
+    match variant {
+        Variant::Legacy => "legacy",
+        Variant::Toml => "TOML",
+    }
+}
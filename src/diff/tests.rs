@@ -0,0 +1,183 @@
+use crate::diff::{Change, Direction};
+use crate::test_helpers::{legacy, toml};
+use crate::{RustToolchainToml, ToolchainFile, Variant};
+use yare::parameterized;
+
+fn channel(name: &str) -> ToolchainFile {
+    toml(RustToolchainToml::builder().with_channel(name))
+}
+
+#[test]
+fn no_changes_after_normalization() {
+    let from = toml(
+        RustToolchainToml::builder()
+            .with_channel("1.70.0")
+            .with_components(["rustfmt", "clippy-preview"]),
+    );
+    let to = toml(
+        RustToolchainToml::builder()
+            .with_channel("1.70.0")
+            .with_components(["clippy", "rustfmt"]),
+    );
+
+    let diff = from.diff(&to);
+
+    assert!(diff.is_empty());
+    assert_eq!(diff.to_string(), "");
+    #[cfg(feature = "json")]
+    assert_eq!(diff.to_json(), "[]");
+}
+
+#[parameterized(
+    upgrade = { "1.70.0", "1.72.1", Some(Direction::Upgrade), Some(2) },
+    downgrade = { "1.72", "1.70", Some(Direction::Downgrade), Some(2) },
+    patch = { "1.72.0", "1.72.1", Some(Direction::Upgrade), Some(0) },
+    completed_patch = { "1.72", "1.72.0", Some(Direction::Downgrade), Some(0) },
+    nightly = { "nightly-2023-06-01", "nightly-2023-01-01", Some(Direction::Downgrade), None },
+    named = { "stable", "beta", None, None },
+)]
+fn channel_change(from: &str, to: &str, direction: Option<Direction>, releases: Option<u64>) {
+    let diff = channel(from).diff(&channel(to));
+
+    let [Change::Channel {
+        direction: actual_direction,
+        releases: actual_releases,
+        ..
+    }] = diff.changes()
+    else {
+        panic!("expected a single channel change, but got: {diff:?}");
+    };
+
+    assert_eq!(*actual_direction, direction);
+    assert_eq!(*actual_releases, releases);
+}
+
+#[test]
+fn components_targets_and_profile() {
+    let from = toml(
+        RustToolchainToml::builder()
+            .with_channel("stable")
+            .with_components(["clippy", "rustfmt"])
+            .with_targets(["wasm32-unknown-unknown"])
+            .with_profile("minimal"),
+    );
+    let to = toml(
+        RustToolchainToml::builder()
+            .with_channel("stable")
+            .with_components(["clippy", "rust-src"])
+            .with_profile("default"),
+    );
+
+    assert_eq!(
+        from.diff(&to).to_string(),
+        "removed component 'rustfmt'\n\
+         added component 'rust-src'\n\
+         removed target 'wasm32-unknown-unknown'\n\
+         changed profile from 'minimal' to 'default'\n"
+    );
+}
+
+#[test]
+fn legacy_to_toml() {
+    let to = toml(
+        RustToolchainToml::builder()
+            .with_channel("stable")
+            .with_component("clippy"),
+    );
+
+    let diff = legacy("stable").diff(&to);
+
+    assert_eq!(
+        diff.changes()[0],
+        Change::Variant {
+            from: Variant::Legacy,
+            to: Variant::Toml
+        }
+    );
+    assert_eq!(
+        diff.to_string(),
+        "switched from a legacy to a TOML toolchain file\nadded component 'clippy'\n"
+    );
+}
+
+#[test]
+fn spec_to_path() {
+    let from = toml(
+        RustToolchainToml::builder()
+            .with_channel("stable")
+            .with_profile("minimal"),
+    );
+    let to = toml(RustToolchainToml::builder().with_path("/opt/rust"));
+
+    assert_eq!(
+        from.diff(&to).to_string(),
+        "replaced the toolchain specification by toolchain path '/opt/rust'\n\
+         removed channel 'stable'\n\
+         removed profile 'minimal'\n"
+    );
+}
+
+#[test]
+fn path_to_spec() {
+    let from = toml(RustToolchainToml::builder().with_path("/opt/rust"));
+
+    assert_eq!(
+        from.diff(&channel("1.70")).to_string(),
+        "replaced toolchain path '/opt/rust' by a toolchain specification\n\
+         set channel to '1.70'\n"
+    );
+}
+
+#[test]
+fn path_change() {
+    let diff = legacy("/opt/rust").diff(&legacy("/opt/other"));
+
+    assert_eq!(
+        diff.to_string(),
+        "changed path from '/opt/rust' to '/opt/other'\n"
+    );
+}
+
+#[parameterized(
+    upgrade = { "1.70.0", "1.72.1", "changed channel from '1.70.0' to '1.72.1' (upgrade by 2 releases)\n" },
+    single_release = { "1.71.0", "1.70.0", "changed channel from '1.71.0' to '1.70.0' (downgrade by 1 release)\n" },
+    patch = { "1.72.0", "1.72.1", "changed channel from '1.72.0' to '1.72.1' (upgrade)\n" },
+    as_written = { "1.70", "1.72", "changed channel from '1.70' to '1.72' (upgrade by 2 releases)\n" },
+    named = { "stable", "beta", "changed channel from 'stable' to 'beta'\n" },
+)]
+fn channel_message(from: &str, to: &str, expected: &str) {
+    assert_eq!(channel(from).diff(&channel(to)).to_string(), expected);
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn json() {
+    let from = legacy("1.70");
+    let to = toml(
+        RustToolchainToml::builder()
+            .with_channel("1.72.1")
+            .with_component("clippy")
+            .with_profile("minimal"),
+    );
+
+    assert_eq!(
+        from.diff(&to).to_json(),
+        concat!(
+            r#"[{"kind":"variant","value":{"from":"legacy","to":"toml"}},"#,
+            r#"{"kind":"channel","value":{"from":"1.70","to":"1.72.1","direction":"upgrade","releases":2}},"#,
+            r#"{"kind":"component_added","value":"clippy"},"#,
+            r#"{"kind":"profile","value":{"from":null,"to":"minimal"}}]"#,
+        )
+    );
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn json_escapes_strings() {
+    let diff = legacy(r"C:\toolchains\rust").diff(&legacy("/opt/\"rust\""));
+
+    assert_eq!(
+        diff.to_json(),
+        r#"[{"kind":"path","value":{"from":"C:\\toolchains\\rust","to":"/opt/\"rust\""}}]"#
+    );
+}
@@ -0,0 +1,152 @@
+//! A stable fingerprint of the toolchain which a toolchain file selects, for example as a CI cache
+//! key.
+
+use crate::toml::{ToolchainSection, ToolchainSpec};
+use crate::{RustToolchainToml, ToolchainFile};
+use alloc::string::String;
+use core::fmt;
+
+#[cfg(test)]
+mod tests;
+
+const FNV_OFFSET_BASIS: u128 = 0x6c62272e07bb014262b821756295c58d;
+const FNV_PRIME: u128 = 0x0000000001000000000000000000013b;
+
+impl ToolchainFile {
+    /// Compute the fingerprint of the toolchain selected by this file.
+    ///
+    /// The fingerprint only depends on the toolchain, and not on how it is written: files which are
+    /// equal after normalization (see [`ToolchainSpec::normalize`]) share a fingerprint, whether they
+    /// are legacy or TOML files, and regardless of whitespace and comments. The channel is hashed as
+    /// written though, since `1.72` selects the latest patch release rather than `1.72.0`.
+    ///
+    /// The fingerprint is the 128-bit FNV-1a hash of the following encoding, in which every value is
+    /// preceded by its length in bytes, and followed by a newline:
+    ///
+    /// ```text
+    /// rust-toolchain-fingerprint-v1
+    /// path:<len>:<path>
+    /// channel:<len>:<channel>
+    /// component:<len>:<component>
+    /// target:<len>:<target>
+    /// profile:<len>:<profile>
+    /// ```
+    ///
+    /// Absent keys are left out, and there is a line for each of the sorted components and targets.
+    /// A path is resolved lexically: `.` segments are removed, `..` segments are applied, and
+    /// backslashes are replaced by slashes, unless the path is an absolute POSIX path. Since the
+    /// path is not resolved against the location of the file, the fingerprint is the same on every
+    /// machine. The encoding and hash are part of the public API, and only change with a new
+    /// version line.
+    pub fn fingerprint(&self) -> Fingerprint {
+        let file = RustToolchainToml::from(self.clone()).normalize_except_channel();
+        let mut hasher = Hasher::new();
+
+        hasher.write_line("rust-toolchain-fingerprint-v1");
+
+        match file.toolchain() {
+            ToolchainSection::Path(path) => {
+                hasher.write_value("path", &path.portable_path().normalized());
+            }
+            ToolchainSection::Spec(spec) => write_spec(&mut hasher, spec),
+        }
+
+        Fingerprint(hasher.finish())
+    }
+}
+
+fn write_spec(hasher: &mut Hasher, spec: &ToolchainSpec) {
+    if let Some(channel) = spec.channel() {
+        hasher.write_value("channel", channel.name());
+    }
+
+    for component in spec.components().unwrap_or_default() {
+        hasher.write_value("component", component.name());
+    }
+
+    for target in spec.targets().unwrap_or_default() {
+        hasher.write_value("target", target.name());
+    }
+
+    if let Some(profile) = spec.profile() {
+        hasher.write_value("profile", profile.name());
+    }
+}
+
+/// The 128-bit FNV-1a hash.
+struct Hasher {
+    state: u128,
+}
+
+impl Hasher {
+    fn new() -> Self {
+        Self {
+            state: FNV_OFFSET_BASIS,
+        }
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.state ^= u128::from(*byte);
+            self.state = self.state.wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    fn write_line(&mut self, line: &str) {
+        self.write(line.as_bytes());
+        self.write(b"\n");
+    }
+
+    fn write_value(&mut self, key: &str, value: &str) {
+        self.write_line(&alloc::format!("{key}:{}:{value}", value.len()));
+    }
+
+    fn finish(&self) -> u128 {
+        self.state
+    }
+}
+
+/// The fingerprint of a toolchain, see [`ToolchainFile::fingerprint`].
+///
+/// It is displayed as 32 lowercase hexadecimal digits.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Fingerprint(u128);
+
+impl Fingerprint {
+    pub fn as_u128(&self) -> u128 {
+        self.0
+    }
+}
+
+impl fmt::Display for Fingerprint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:032x}", self.0)
+    }
+}
+
+impl serde::Serialize for Fingerprint {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Fingerprint {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let digits = String::deserialize(deserializer)?;
+
+        if digits.len() != 32
+            || !digits
+                .bytes()
+                .all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+        {
+            return Err(serde::de::Error::invalid_value(
+                serde::de::Unexpected::Str(&digits),
+                &"32 lowercase hexadecimal digits",
+            ));
+        }
+
+        u128::from_str_radix(&digits, 16)
+            .map(Self)
+            .map_err(serde::de::Error::custom)
+    }
+}
@@ -0,0 +1,99 @@
+use crate::fingerprint::Hasher;
+use crate::test_helpers::{legacy, toml};
+use crate::{RustToolchainToml, ToolchainFile};
+use yare::parameterized;
+
+#[test]
+fn fnv_1a_128() {
+    let mut hasher = Hasher::new();
+    hasher.write(b"a");
+
+    assert_eq!(hasher.finish(), 0xd228cb696f1a8caf78912b704e4a8964);
+}
+
+#[parameterized(
+    legacy_file = { legacy("stable") },
+    toml_file = { toml(RustToolchainToml::builder().with_channel("stable")) },
+    empty_components = { toml(RustToolchainToml::builder().with_channel("stable").with_components(Vec::<String>::new())) },
+)]
+fn same_as_stable(file: ToolchainFile) {
+    assert_eq!(file.fingerprint(), legacy("stable").fingerprint(),);
+}
+
+#[test]
+fn normalized_spec() {
+    let left = toml(
+        RustToolchainToml::builder()
+            .with_channel("1.70.0")
+            .with_components(["rustfmt", "clippy-preview", "rustfmt"])
+            .with_targets(["wasm32-unknown-unknown", "aarch64-apple-darwin"]),
+    );
+    let right = toml(
+        RustToolchainToml::builder()
+            .with_channel("1.70.0")
+            .with_components(["clippy", "rustfmt"])
+            .with_targets(["aarch64-apple-darwin", "wasm32-unknown-unknown"]),
+    );
+
+    assert_eq!(left.fingerprint(), right.fingerprint());
+}
+
+#[test]
+fn completed_patch_version() {
+    let left = toml(RustToolchainToml::builder().with_channel("1.72"));
+    let right = toml(RustToolchainToml::builder().with_channel("1.72.0"));
+
+    assert_ne!(left.fingerprint(), right.fingerprint());
+}
+
+#[parameterized(
+    channel = { toml(RustToolchainToml::builder().with_channel("beta")) },
+    component = { toml(RustToolchainToml::builder().with_channel("stable").with_component("clippy")) },
+    target = { toml(RustToolchainToml::builder().with_channel("stable").with_target("clippy")) },
+    profile = { toml(RustToolchainToml::builder().with_channel("stable").with_profile("minimal")) },
+    toolchain_path = { legacy("/stable") },
+)]
+fn differs_from_stable(file: ToolchainFile) {
+    assert_ne!(file.fingerprint(), legacy("stable").fingerprint());
+}
+
+#[test]
+fn values_can_not_be_confused() {
+    let left = toml(
+        RustToolchainToml::builder()
+            .with_channel("stable")
+            .with_components(["a", "b"]),
+    );
+    let right = toml(
+        RustToolchainToml::builder()
+            .with_channel("stable")
+            .with_components(["a\ncomponent:1:b"]),
+    );
+
+    assert_ne!(left.fingerprint(), right.fingerprint());
+}
+
+/// The fingerprints must stay the same across releases and platforms.
+#[parameterized(
+    stable = { legacy("stable"), "1edac8317d88ebc442f98b990492d42a" },
+    spec = {
+        toml(RustToolchainToml::builder()
+            .with_channel("nightly-2020-07-10")
+            .with_components(["rustfmt", "rustc-dev"])
+            .with_targets(["wasm32-unknown-unknown", "thumbv2-none-eabi"])
+            .with_profile("minimal")),
+        "26f62bf5a7178a7ac77c34ac4c752c13"
+    },
+    path = { legacy("/opt/rust"), "5d2d85ce6e160cf0248086569e05efc5" },
+)]
+fn stable_fingerprint(file: ToolchainFile, expected: &str) {
+    assert_eq!(file.fingerprint().to_string(), expected);
+}
+
+#[test]
+fn equivalent_paths() {
+    let left = toml(RustToolchainToml::builder().with_path("./toolchains/rust"));
+    let right = toml(RustToolchainToml::builder().with_path(r"toolchains\rust"));
+
+    assert_eq!(left.fingerprint(), right.fingerprint());
+}
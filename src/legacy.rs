@@ -1,17 +1,26 @@
-use std::path::{Path, PathBuf};
-use std::str::FromStr;
+use crate::options::{Encoding, Whitespace};
+use crate::{ParserOptions, PortablePath, Warning};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+use core::str::FromStr;
 
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
+#[allow(deprecated)]
 mod tests;
 
+mod grammar;
+
+pub use grammar::{validate, GrammarViolation};
+
 /// A parser for the legacy toolchain file format.
 pub struct Parser<'content> {
     content: &'content str,
 
     /// According to the rustup book, the legacy format must be encoded as US-ASCII without BOM.
     ///
-    /// Setting this field to true, we'll be more lenient, and allow the input to be encoded as UTF-8.
-    strict: bool,
+    /// By default, we'll be more lenient, and allow the input to be encoded as UTF-8, like rustup does.
+    options: ParserOptions,
 }
 
 impl<'content> Parser<'content> {
@@ -19,7 +28,7 @@ impl<'content> Parser<'content> {
     pub fn new(content: &'content str) -> Self {
         Self {
             content,
-            strict: false,
+            options: ParserOptions::default(),
         }
     }
 
@@ -27,57 +36,132 @@ impl<'content> Parser<'content> {
     pub fn strict(content: &'content str) -> Self {
         Self {
             content,
-            strict: true,
+            options: ParserOptions::default().with_encoding(Encoding::Ascii),
         }
     }
+
+    /// Replace the options used by this parser.
+    pub fn with_options(mut self, options: ParserOptions) -> Self {
+        self.options = options;
+        self
+    }
 }
 
 impl Parser<'_> {
     pub fn parse(&self) -> Result<LegacyToolchainFile, ParserError> {
+        self.parse_with_warnings().map(|(file, _)| file)
+    }
+
+    /// Parse the content, and collect the warnings which were not promoted to errors.
+    pub fn parse_with_warnings(&self) -> Result<(LegacyToolchainFile, Vec<Warning>), ParserError> {
+        let mut warnings = Vec::new();
+
         // Verify the required encoding.
-        if self.strict && !self.content.is_ascii() {
+        if self.options.encoding() == Encoding::Ascii && !self.content.is_ascii() {
             return Err(ParserError::InvalidEncodingStrict);
         }
 
-        let content = self.content.trim();
+        let limits = self.options.limits();
+
+        if self.content.len() > limits.max_bytes() {
+            return Err(ParserError::TooLarge(limits.max_bytes()));
+        }
+
+        self.options
+            .check_whitespace(self.content.as_bytes(), &mut warnings)
+            .map_err(ParserError::Denied)?;
+
+        let content = match self.options.whitespace() {
+            Whitespace::Lenient => self.content.trim_start_matches('\u{FEFF}').trim(),
+            Whitespace::Strict | Whitespace::Trim => self.content.trim(),
+        };
 
         // Verify, that there is content
         if content.is_empty() {
             return Err(ParserError::IsEmpty);
         }
 
+        if let Err(violation) = validate(content) {
+            self.options
+                .report(
+                    self.options.legacy_grammar(),
+                    Warning::LegacyGrammar(violation),
+                    &mut warnings,
+                )
+                .map_err(ParserError::Denied)?;
+        }
+
         // Verify the contents consist of one specifier or path, on a single line
-        let line_count = content.lines().count();
+        let lines = content
+            .lines()
+            .filter(|line| {
+                self.options.whitespace() != Whitespace::Lenient || !line.trim().is_empty()
+            })
+            .collect::<Vec<_>>();
+
+        let content = match lines.as_slice() {
+            [line] => line.trim(),
+            _ => return Err(ParserError::TooManyLines(lines.len())),
+        };
 
-        if line_count != 1 {
-            return Err(ParserError::TooManyLines(line_count));
+        if content.len() > limits.max_string_len() {
+            return Err(ParserError::StringTooLong(limits.max_string_len()));
         }
 
         // Set the channel type
-        let channel = if Path::new(content).is_absolute() {
-            LegacyChannel::Path(content.into())
+        let path = PortablePath::from(content);
+
+        let channel = if path.is_absolute() {
+            self.options
+                .check_platform(&path, &mut warnings)
+                .map_err(ParserError::Denied)?;
+
+            LegacyChannel::Path(path)
         } else {
             LegacyChannel::Spec(content.to_string())
         };
 
-        Ok(LegacyToolchainFile { channel })
+        let file = LegacyToolchainFile { channel };
+
+        self.options
+            .check_rustup_features(&file.rustup_features())
+            .map_err(ParserError::Denied)?;
+
+        Ok((file, warnings))
     }
 }
 
-#[derive(Debug, thiserror::Error, PartialEq)]
+#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "kind", content = "value", rename_all = "snake_case")]
 pub enum ParserError {
-    #[error("Unable to parse legacy toolchain file: toolchain file was empty")]
     IsEmpty,
-
-    #[error("Encountered invalid encoding while parsing legacy rust-toolchain file. The expected encoding to be US-ASCII, and lenient encoding was disabled.")]
     InvalidEncodingStrict,
-
-    #[error("Expected a single line containing the toolchain specifier but found '{0}' lines.")]
+    InvalidUtf8,
     TooManyLines(usize),
+    Denied(Warning),
+    TooLarge(usize),
+    StringTooLong(usize),
+}
+
+impl fmt::Display for ParserError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::IsEmpty => f.write_str("Unable to parse legacy toolchain file: toolchain file was empty"),
+            Self::InvalidEncodingStrict => f.write_str("Encountered invalid encoding while parsing legacy rust-toolchain file. The expected encoding to be US-ASCII, and lenient encoding was disabled."),
+            Self::InvalidUtf8 => f.write_str("Encountered invalid encoding while parsing legacy rust-toolchain file. The content is not valid UTF-8."),
+            Self::TooManyLines(lines) => write!(f, "Expected a single line containing the toolchain specifier but found '{lines}' lines."),
+            Self::Denied(warning) => write!(f, "Rejected legacy toolchain file: {warning}"),
+            Self::TooLarge(limit) => write!(f, "Unable to parse legacy toolchain file: the content exceeds the limit of {limit} bytes"),
+            Self::StringTooLong(limit) => write!(f, "Unable to parse legacy toolchain file: the toolchain specifier exceeds the length limit of {limit} bytes"),
+        }
+    }
 }
 
+#[cfg(feature = "std")]
+impl std::error::Error for ParserError {}
+
 /// The legacy toolchain file variant
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct LegacyToolchainFile {
     channel: LegacyChannel,
 }
@@ -103,11 +187,22 @@ impl LegacyToolchainFile {
         &self.channel
     }
 
+    pub fn into_channel(self) -> LegacyChannel {
+        self.channel
+    }
+
     /// Return the toolchain path, given that the toolchain-file contents
     /// consists of a path and not a channel specification.
-    pub fn path(&self) -> Option<&Path> {
+    #[cfg(feature = "std")]
+    pub fn path(&self) -> Option<&std::path::Path> {
+        self.portable_path().map(PortablePath::as_path)
+    }
+
+    /// Return the toolchain path as written, given that the toolchain-file contents consists of a path
+    /// and not a channel specification.
+    pub fn portable_path(&self) -> Option<&PortablePath> {
         match self.channel {
-            LegacyChannel::Path(ref p) => Some(p.as_path()),
+            LegacyChannel::Path(ref p) => Some(p),
             _ => None,
         }
     }
@@ -120,11 +215,76 @@ impl LegacyToolchainFile {
             _ => None,
         }
     }
+
+    /// Write the toolchain file in the legacy format: the toolchain name or path, followed by a
+    /// newline.
+    ///
+    /// Fails if parsing the output with the default options would not result in an equal value,
+    /// for example because the name spans multiple lines, or a relative path would be read as a
+    /// toolchain name.
+    pub fn to_legacy_string(&self) -> Result<String, WriteError> {
+        let content = match &self.channel {
+            LegacyChannel::Path(path) if path.is_relative() => {
+                return Err(WriteError::RelativePath)
+            }
+            LegacyChannel::Path(path) => path.as_str(),
+            LegacyChannel::Spec(spec) if PortablePath::from(spec.as_str()).is_absolute() => {
+                return Err(WriteError::AbsoluteSpec)
+            }
+            LegacyChannel::Spec(spec) => spec.as_str(),
+        };
+
+        if content.is_empty() {
+            return Err(WriteError::IsEmpty);
+        }
+
+        if content.contains('\n') {
+            return Err(WriteError::MultipleLines);
+        }
+
+        if content.trim() != content {
+            return Err(WriteError::SurroundingWhitespace);
+        }
+
+        let mut out = String::from(content);
+        out.push('\n');
+
+        Ok(out)
+    }
+}
+
+/// The reason a [`LegacyToolchainFile`] can not be written in the legacy format.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WriteError {
+    IsEmpty,
+    MultipleLines,
+    SurroundingWhitespace,
+    /// A toolchain name which would be read as an absolute path.
+    AbsoluteSpec,
+    /// A relative path, which would be read as a toolchain name.
+    RelativePath,
+}
+
+impl fmt::Display for WriteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::IsEmpty => "Unable to write legacy toolchain file: the toolchain name is empty",
+            Self::MultipleLines => "Unable to write legacy toolchain file: the toolchain name spans multiple lines",
+            Self::SurroundingWhitespace => "Unable to write legacy toolchain file: the toolchain name is surrounded by whitespace",
+            Self::AbsoluteSpec => "Unable to write legacy toolchain file: the toolchain name would be read as an absolute path",
+            Self::RelativePath => "Unable to write legacy toolchain file: a relative path would be read as a toolchain name",
+        })
+    }
 }
 
+#[cfg(feature = "std")]
+impl std::error::Error for WriteError {}
+
 /// The channel specified within the legacy toolchain file.
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "kind", content = "value", rename_all = "snake_case")]
 pub enum LegacyChannel {
-    Path(PathBuf),
+    Path(PortablePath),
     Spec(String),
 }
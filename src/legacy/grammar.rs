@@ -0,0 +1,87 @@
+use crate::PortablePath;
+use core::fmt;
+
+#[cfg(test)]
+mod tests;
+
+/// A reason why the content of a legacy toolchain file is not a valid toolchain name or path.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "kind", content = "value", rename_all = "snake_case")]
+pub enum GrammarViolation {
+    /// A line starts with `#`, but legacy toolchain files do not support comments.
+    Comment,
+    /// The content contains a table header or a key-value pair.
+    TomlContent,
+    /// The toolchain name is surrounded by, or contains, quotes.
+    Quotes,
+    /// The toolchain name contains whitespace, like `nightly 2020-07-10`.
+    Whitespace,
+    /// The path is not absolute, which rustup requires for paths in legacy toolchain files.
+    RelativePath,
+    /// The toolchain name contains a character which is not allowed in toolchain names.
+    InvalidCharacter(char),
+}
+
+impl fmt::Display for GrammarViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Comment => f.write_str("legacy toolchain files do not support comments"),
+            Self::TomlContent => f.write_str("the content looks like TOML, but a legacy toolchain file may only contain a toolchain name or path"),
+            Self::Quotes => f.write_str("the toolchain name contains quotes, which are not removed"),
+            Self::Whitespace => f.write_str("the toolchain name contains whitespace"),
+            Self::RelativePath => f.write_str("the toolchain path is relative, but must be absolute"),
+            Self::InvalidCharacter(c) => {
+                write!(f, "the toolchain name contains the invalid character '{c}'")
+            }
+        }
+    }
+}
+
+/// Validate the trimmed content of a legacy toolchain file against rustup's toolchain name grammar,
+/// and its rules for toolchain paths.
+///
+/// A toolchain name, such as `stable`, `1.70.0`, `nightly-2020-07-10` or
+/// `stable-x86_64-pc-windows-msvc`, consists of ASCII letters, digits, `-`, `.`, `_` and `+`. Any other
+/// content must be an absolute path.
+pub fn validate(content: &str) -> Result<(), GrammarViolation> {
+    let lines = content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty());
+
+    // An absolute path may contain any character, like `#` or `=`.
+    for line in lines.filter(|line| !PortablePath::from(*line).is_absolute()) {
+        if line.starts_with('#') {
+            return Err(GrammarViolation::Comment);
+        }
+
+        if line.starts_with('[') || line.contains('=') {
+            return Err(GrammarViolation::TomlContent);
+        }
+    }
+
+    // Multiple lines are rejected by the parser itself.
+    if content.lines().count() > 1 || PortablePath::from(content).is_absolute() {
+        return Ok(());
+    }
+
+    if content.contains(['"', '\'']) {
+        return Err(GrammarViolation::Quotes);
+    }
+
+    if content.contains(char::is_whitespace) {
+        return Err(GrammarViolation::Whitespace);
+    }
+
+    if content.contains(['/', '\\']) {
+        return Err(GrammarViolation::RelativePath);
+    }
+
+    match content
+        .chars()
+        .find(|&c| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '_' | '+')))
+    {
+        Some(c) => Err(GrammarViolation::InvalidCharacter(c)),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,39 @@
+use crate::legacy::{validate, GrammarViolation};
+use yare::parameterized;
+
+#[parameterized(
+    stable = { "stable" },
+    beta = { "beta" },
+    nightly = { "nightly" },
+    version = { "1.70" },
+    full_version = { "1.70.0" },
+    dated_nightly = { "nightly-2020-07-10" },
+    with_host = { "stable-x86_64-pc-windows-msvc" },
+    custom = { "my_toolchain+local" },
+    posix_path = { "/opt/rust" },
+    windows_path = { r"C:\Program Files\rust" },
+    path_with_equals_sign = { "/opt/rust=1.70" },
+    path_with_brackets = { "/opt/[rust]" },
+    windows_path_with_hash = { r"C:\rust#1" },
+)]
+fn valid(content: &str) {
+    assert_eq!(validate(content), Ok(()));
+}
+
+#[parameterized(
+    comment = { "# pinned toolchain", GrammarViolation::Comment },
+    comment_before_spec = { "# pinned\nstable", GrammarViolation::Comment },
+    table_header = { "[toolchain]", GrammarViolation::TomlContent },
+    key_value = { "toolchain = stable", GrammarViolation::TomlContent },
+    double_quoted = { "\"stable\"", GrammarViolation::Quotes },
+    single_quoted = { "'stable'", GrammarViolation::Quotes },
+    space = { "nightly 2020-07-10", GrammarViolation::Whitespace },
+    tab = { "nightly\t2020-07-10", GrammarViolation::Whitespace },
+    relative_path = { "toolchains/rust", GrammarViolation::RelativePath },
+    relative_windows_path = { r"toolchains\rust", GrammarViolation::RelativePath },
+    invalid_character = { "stable!", GrammarViolation::InvalidCharacter('!') },
+    non_ascii = { "stäble", GrammarViolation::InvalidCharacter('ä') },
+)]
+fn invalid(content: &str, expected: GrammarViolation) {
+    assert_eq!(validate(content), Err(expected));
+}
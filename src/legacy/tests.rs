@@ -1,7 +1,7 @@
 use super::Parser;
 use crate::legacy::{LegacyChannel, ParserError};
-use crate::LegacyToolchainFile;
-use std::path::{Path, PathBuf};
+use crate::{LegacyToolchainFile, PortablePath};
+use std::path::Path;
 use yare::parameterized;
 
 yare::ide!();
@@ -125,8 +125,8 @@ fn parse_err_line_count(parser: fn(&str) -> Parser) {
 }
 
 #[parameterized(
-    lenient_path = { sample_path(), |content: &str| Parser::new(content), |content: &str| LegacyChannel::Path(PathBuf::from(content))  },
-    strict_path = { sample_path(), |content: &str| Parser::strict(content), |content: &str| LegacyChannel::Path(PathBuf::from(content)) },
+    lenient_path = { sample_path(), |content: &str| Parser::new(content), |content: &str| LegacyChannel::Path(PortablePath::from(content))  },
+    strict_path = { sample_path(), |content: &str| Parser::strict(content), |content: &str| LegacyChannel::Path(PortablePath::from(content)) },
     lenient_spec = { "channel", |content: &str| Parser::new(content), |content: &str| LegacyChannel::Spec(String::from(content))  },
     strict_spec = { "channel", |content: &str| Parser::strict(content), |content: &str| LegacyChannel::Spec(String::from(content)) },
 )]
@@ -171,7 +171,7 @@ fn get_spec(parser: fn(&str) -> Parser) {
 }
 
 #[parameterized(
-    path = { sample_path(), |content: &str| Ok(LegacyToolchainFile { channel: LegacyChannel::Path(PathBuf::from(content)) }) },
+    path = { sample_path(), |content: &str| Ok(LegacyToolchainFile { channel: LegacyChannel::Path(PortablePath::from(content)) }) },
     spec = { "channel", |content: &str| Ok(LegacyToolchainFile { channel: LegacyChannel::Spec(String::from(content)) }) },
     spec_lenient = { "😉", |content: &str| Ok(LegacyToolchainFile { channel: LegacyChannel::Spec(String::from(content)) }) },
     spec_leniet = { "a\nb", |_content: &str| Err(ParserError::TooManyLines(2)) },
@@ -220,3 +220,291 @@ fn error_message(content: &str, error_message: &str) {
 
     assert_eq!(&message, error_message);
 }
+
+mod options {
+    use crate::legacy::{LegacyChannel, Parser, ParserError};
+    use crate::options::Whitespace;
+    use crate::{ParserOptions, Warning};
+    use yare::parameterized;
+
+    #[parameterized(
+        bom = { "\u{FEFF}stable" },
+        blank_lines = { "\n\nstable\n\n  \n" },
+        bom_and_blank_lines = { "\u{FEFF}\n  stable  \n\n" },
+    )]
+    fn lenient_accepts(content: &str) {
+        let parser = Parser::new(content).with_options(ParserOptions::lenient());
+
+        let result = parser.parse();
+        assert_eq!(
+            result.unwrap().channel(),
+            &LegacyChannel::Spec("stable".to_string())
+        );
+    }
+
+    #[test]
+    fn lenient_rejects_multiple_channels() {
+        let parser = Parser::new("stable\n\nnightly").with_options(ParserOptions::lenient());
+
+        let result = parser.parse();
+        assert_eq!(result.unwrap_err(), ParserError::TooManyLines(2));
+    }
+
+    #[test]
+    fn rustup_exact_rejects_blank_lines() {
+        let parser = Parser::new("stable\n\nnightly").with_options(ParserOptions::rustup_exact());
+
+        let result = parser.parse();
+        assert_eq!(result.unwrap_err(), ParserError::TooManyLines(3));
+    }
+
+    #[test]
+    fn rustup_exact_keeps_bom() {
+        let parser = Parser::new("\u{FEFF}stable").with_options(ParserOptions::rustup_exact());
+
+        let result = parser.parse();
+        assert_eq!(result.unwrap().spec(), Some("\u{FEFF}stable"));
+    }
+
+    #[parameterized(
+        non_ascii = { "❤️", ParserError::InvalidEncodingStrict },
+        surrounding_whitespace = { " stable", ParserError::Denied(Warning::SurroundingWhitespace) },
+    )]
+    fn pedantic_rejects(content: &str, expected: ParserError) {
+        let parser = Parser::new(content).with_options(ParserOptions::pedantic());
+
+        let result = parser.parse();
+        assert_eq!(result.unwrap_err(), expected);
+    }
+
+    #[test]
+    fn pedantic_accepts_trailing_newline() {
+        let parser = Parser::new("stable\n").with_options(ParserOptions::pedantic());
+
+        let result = parser.parse();
+        assert_eq!(result.unwrap().spec(), Some("stable"));
+    }
+
+    #[test]
+    fn warnings_are_collected() {
+        let options = ParserOptions::pedantic().with_warnings_as_errors(false);
+        let parser = Parser::new(" stable ").with_options(options);
+
+        let (file, warnings) = parser.parse_with_warnings().unwrap();
+        assert_eq!(file.spec(), Some("stable"));
+        assert_eq!(warnings, vec![Warning::SurroundingWhitespace]);
+    }
+
+    #[test]
+    fn strict_whitespace_without_warnings_as_errors() {
+        let options = ParserOptions::rustup_exact().with_whitespace(Whitespace::Strict);
+        let parser = Parser::new("\nstable").with_options(options);
+
+        let (_, warnings) = parser.parse_with_warnings().unwrap();
+        assert_eq!(warnings, vec![Warning::SurroundingWhitespace]);
+    }
+}
+
+mod limits {
+    use crate::legacy::{Parser, ParserError};
+    use crate::options::Limits;
+    use crate::ParserOptions;
+
+    fn parser(content: &str, limits: Limits) -> Parser<'_> {
+        Parser::new(content).with_options(ParserOptions::default().with_limits(limits))
+    }
+
+    #[test]
+    fn default_limits() {
+        let result = parser("nightly-2020-07-10", Limits::default()).parse();
+
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn too_large() {
+        let content = format!("{}nightly", " ".repeat(32));
+        let result = parser(&content, Limits::default().with_max_bytes(16)).parse();
+
+        assert_eq!(result.unwrap_err(), ParserError::TooLarge(16));
+    }
+
+    #[test]
+    fn string_too_long() {
+        let result = parser(
+            "nightly-2020-07-10",
+            Limits::default().with_max_string_len(8),
+        )
+        .parse();
+
+        assert_eq!(result.unwrap_err(), ParserError::StringTooLong(8));
+    }
+}
+
+mod platform {
+    use crate::legacy::{LegacyChannel, Parser, ParserError};
+    use crate::options::Level;
+    use crate::path::Platform;
+    use crate::{ParserOptions, PortablePath, Warning};
+    use yare::parameterized;
+
+    #[parameterized(
+        posix = { "/opt/rust" },
+        windows_drive = { r"C:\toolchains\rust" },
+        windows_unc = { r"\\server\share\rust" },
+    )]
+    fn paths_of_any_platform(content: &str) {
+        let result = Parser::new(content).parse().unwrap();
+
+        assert_eq!(
+            result.channel(),
+            &LegacyChannel::Path(PortablePath::from(content))
+        );
+    }
+
+    #[test]
+    fn platform_specific_path_warns() {
+        let options = ParserOptions::rustup_exact().with_platform_specific_paths(Level::Warn);
+        let parser = Parser::new(r"C:\toolchains\rust").with_options(options);
+
+        let (_, warnings) = parser.parse_with_warnings().unwrap();
+        assert_eq!(
+            warnings,
+            vec![Warning::PlatformSpecificPath {
+                path: r"C:\toolchains\rust".to_string(),
+                platform: Platform::Windows,
+            }]
+        );
+    }
+
+    #[test]
+    fn pedantic_rejects_platform_specific_path() {
+        let parser = Parser::new("/opt/rust").with_options(ParserOptions::pedantic());
+
+        assert_eq!(
+            parser.parse().unwrap_err(),
+            ParserError::Denied(Warning::PlatformSpecificPath {
+                path: "/opt/rust".to_string(),
+                platform: Platform::Posix,
+            })
+        );
+    }
+}
+
+mod grammar {
+    use crate::legacy::{GrammarViolation, Parser, ParserError};
+    use crate::options::Level;
+    use crate::{ParserOptions, Warning};
+
+    #[test]
+    fn rustup_exact_accepts_invalid_grammar() {
+        let parser = Parser::new("nightly 2020-07-10").with_options(ParserOptions::rustup_exact());
+
+        let (file, warnings) = parser.parse_with_warnings().unwrap();
+        assert_eq!(file.spec(), Some("nightly 2020-07-10"));
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn warn_invalid_grammar() {
+        let options = ParserOptions::rustup_exact().with_legacy_grammar(Level::Warn);
+        let parser = Parser::new("\"stable\"").with_options(options);
+
+        let (_, warnings) = parser.parse_with_warnings().unwrap();
+        assert_eq!(
+            warnings,
+            vec![Warning::LegacyGrammar(GrammarViolation::Quotes)]
+        );
+    }
+
+    #[test]
+    fn comment_is_reported_before_too_many_lines() {
+        let options = ParserOptions::rustup_exact().with_legacy_grammar(Level::Deny);
+        let parser = Parser::new("# pinned\nstable").with_options(options);
+
+        assert_eq!(
+            parser.parse().unwrap_err(),
+            ParserError::Denied(Warning::LegacyGrammar(GrammarViolation::Comment))
+        );
+    }
+
+    #[test]
+    fn pedantic_rejects_toml_content() {
+        let parser = Parser::new("toolchain = stable").with_options(ParserOptions::pedantic());
+
+        assert_eq!(
+            parser.parse().unwrap_err(),
+            ParserError::Denied(Warning::LegacyGrammar(GrammarViolation::TomlContent))
+        );
+    }
+
+    #[test]
+    fn grammar_accepts_path_with_equals_sign() {
+        let options = ParserOptions::rustup_exact().with_legacy_grammar(Level::Deny);
+        let parser = Parser::new("/opt/rust=1.70").with_options(options);
+
+        let (_, warnings) = parser.parse_with_warnings().unwrap();
+        assert!(warnings.is_empty());
+    }
+}
+
+mod to_legacy_string {
+    use crate::legacy::{LegacyChannel, Parser, WriteError};
+    use crate::{LegacyToolchainFile, PortablePath};
+    use proptest::prelude::*;
+    use yare::parameterized;
+
+    #[parameterized(
+        spec = { LegacyChannel::Spec("nightly-2020-07-10".to_string()), "nightly-2020-07-10\n" },
+        posix_path = { LegacyChannel::Path(PortablePath::from("/opt/rust")), "/opt/rust\n" },
+        windows_path = { LegacyChannel::Path(PortablePath::from(r"C:\toolchains\rust")), "C:\\toolchains\\rust\n" },
+    )]
+    fn writes(channel: LegacyChannel, expected: &str) {
+        let file = LegacyToolchainFile::new(channel);
+
+        assert_eq!(file.to_legacy_string().unwrap(), expected);
+    }
+
+    #[parameterized(
+        empty = { LegacyChannel::Spec(String::new()), WriteError::IsEmpty },
+        multiple_lines = { LegacyChannel::Spec("stable\nbeta".to_string()), WriteError::MultipleLines },
+        surrounding_whitespace = { LegacyChannel::Spec(" stable".to_string()), WriteError::SurroundingWhitespace },
+        absolute_spec = { LegacyChannel::Spec("/opt/rust".to_string()), WriteError::AbsoluteSpec },
+        relative_path = { LegacyChannel::Path(PortablePath::from("toolchains/rust")), WriteError::RelativePath },
+    )]
+    fn rejects(channel: LegacyChannel, expected: WriteError) {
+        let file = LegacyToolchainFile::new(channel);
+
+        assert_eq!(file.to_legacy_string().unwrap_err(), expected);
+    }
+
+    fn legacy_channel() -> impl Strategy<Value = LegacyChannel> {
+        prop_oneof![
+            any::<String>().prop_map(LegacyChannel::Spec),
+            any::<String>().prop_map(|path| LegacyChannel::Path(PortablePath::new(path))),
+            "[a-z0-9.-]{1,20}".prop_map(LegacyChannel::Spec),
+            "(/|[A-Z]:\\\\)[a-z/\\\\]{0,20}[a-z]"
+                .prop_map(|path| LegacyChannel::Path(PortablePath::new(path))),
+        ]
+    }
+
+    proptest! {
+        #[test]
+        fn written_file_reparses_to_equal_value(channel in legacy_channel()) {
+            let file = LegacyToolchainFile::new(channel);
+
+            if let Ok(content) = file.to_legacy_string() {
+                prop_assert_eq!(Parser::new(&content).parse().unwrap(), file);
+            }
+        }
+
+        #[test]
+        fn parsed_file_can_be_written(content in any::<String>()) {
+            if let Ok(file) = Parser::new(&content).parse() {
+                let written = file.to_legacy_string().unwrap();
+
+                prop_assert_eq!(Parser::new(&written).parse().unwrap(), file);
+            }
+        }
+    }
+}
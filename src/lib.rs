@@ -1,16 +1,60 @@
 //! Modelled after docs published at: <https://rust-lang.github.io/rustup/overrides.html#the-toolchain-file>
+//!
+//! Without the default `std` feature, the crate is `no_std`, and provides the legacy parser and the
+//! toolchain file models on top of `alloc`. Parsing TOML toolchain files, and the combined parser,
+//! require a TOML library, which is selected with the `use_toml_edit` or `use_toml` feature.
 
-// exports
-pub use {legacy::LegacyToolchainFile, toml::RustToolchainToml};
+// Errors and warnings which are available without the `std` feature implement `Display` by hand,
+// since `thiserror` requires the standard library.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
 
+// exports
+pub use {
+    access::ToolchainKind, legacy::LegacyToolchainFile, options::ParserOptions, path::PortablePath,
+    toml::RustToolchainToml, warning::Warning,
+};
+
+#[cfg(any(feature = "use_toml_edit", feature = "use_toml"))]
+pub use {
+    migrate::{migrate, MigrateError, Migration},
+    outcome::ParseOutcome,
+    parser::{FallbackError, Hint, HintedError, Parser, ParserError},
+    read::{ReadError, ReadErrorKind},
+};
+
+pub mod diff;
+pub mod fingerprint;
 pub mod legacy;
+pub mod options;
+pub mod path;
+pub mod requirement;
+pub mod rustup;
+pub mod schema;
 pub mod toml;
 
+mod access;
+#[cfg(any(feature = "use_toml_edit", feature = "use_toml"))]
+mod migrate;
+#[cfg(any(feature = "use_toml_edit", feature = "use_toml"))]
+mod outcome;
+#[cfg(any(feature = "use_toml_edit", feature = "use_toml"))]
+mod parser;
+#[cfg(any(feature = "use_toml_edit", feature = "use_toml"))]
+mod read;
+mod warning;
+
 #[cfg(test)]
+mod test_helpers;
+
+#[cfg(all(test, any(feature = "use_toml_edit", feature = "use_toml")))]
+#[allow(clippy::redundant_static_lifetimes)]
 mod tests;
 
 /// Model of a Rust toolchain file, which can be used to pin a specific toolchain to a Rust project.
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "kind", content = "value", rename_all = "snake_case")]
 pub enum ToolchainFile {
     /// The legacy variant of the toolchain file only specifies the name of a toolchain
     Legacy(LegacyToolchainFile),
@@ -19,99 +63,97 @@ pub enum ToolchainFile {
 }
 
 /// Variants which may be used to identify supported rust-toolchain variants.
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Variant {
     Legacy,
     Toml,
 }
 
 impl Variant {
-    fn parse_with(&self, content: &str) -> Result<ToolchainFile, ParserError> {
-        match *self {
-            Self::Legacy => legacy::Parser::new(content)
-                .parse()
-                .map(ToolchainFile::Legacy)
-                .map_err(From::from),
-            Self::Toml => toml::Parser::new(content)
-                .parse()
-                .map(ToolchainFile::Toml)
-                .map_err(From::from),
+    /// Guess the variant of the given content, without parsing it.
+    ///
+    /// Content which starts with a table header, or contains a key-value pair, looks like TOML, while
+    /// content which consists of a single line looks like a legacy toolchain file. A single absolute
+    /// path always looks like a legacy toolchain file, even if it contains a `=`.
+    pub fn sniff(content: &str) -> Option<Variant> {
+        let content = content.trim();
+
+        if content.lines().count() == 1 && PortablePath::from(content).is_absolute() {
+            Some(Self::Legacy)
+        } else if content.starts_with('[') || content.lines().any(|line| line.contains('=')) {
+            Some(Self::Toml)
+        } else if content.lines().count() == 1 {
+            Some(Self::Legacy)
+        } else {
+            None
         }
     }
 }
 
-/// Option to determine whether only to parse one rust-toolchain variant (TOML, or legacy), or
-/// to fallback to another variant.
-#[derive(Debug, Copy, Clone, PartialEq)]
-pub enum ParseStrategy {
-    Only(Variant),
-    Fallback {
-        first: Variant,
-        fallback_to: Variant,
-    },
-}
-
-/// A combined parser for the legacy and TOML toolchain file formats.
-pub struct Parser<'content> {
-    content: &'content str,
-
-    parse_option: ParseStrategy,
+/// The file names recognized by rustup as a toolchain file.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum FileName {
+    /// `rust-toolchain`, which may contain either a legacy or a TOML toolchain file.
+    #[serde(rename = "rust-toolchain")]
+    RustToolchain,
+    /// `rust-toolchain.toml`, which may only contain a TOML toolchain file.
+    #[serde(rename = "rust-toolchain.toml")]
+    RustToolchainToml,
 }
 
-impl<'content> Parser<'content> {
-    pub fn new(content: &'content str, parse_option: ParseStrategy) -> Self {
-        Self {
-            content,
-            parse_option,
+impl FileName {
+    /// Recognize the file name of the given path, if it is a toolchain file.
+    #[cfg(feature = "std")]
+    pub fn from_path(path: impl AsRef<std::path::Path>) -> Option<Self> {
+        match path.as_ref().file_name()?.to_str()? {
+            "rust-toolchain" => Some(Self::RustToolchain),
+            "rust-toolchain.toml" => Some(Self::RustToolchainToml),
+            _ => None,
         }
     }
-}
 
-impl Parser<'_> {
-    pub fn parse(&self) -> Result<ToolchainFile, ParserError> {
-        match self.parse_option {
-            ParseStrategy::Only(v) => v.parse_with(self.content),
-            ParseStrategy::Fallback { first, fallback_to } => {
-                first.parse_with(self.content).or_else(|original_err| {
-                    fallback_to
-                        .parse_with(self.content)
-                        .map_err(|fallback_err| {
-                            ParserError::FallbackError(FallbackError {
-                                first: Box::new(original_err),
-                                fallback_to: Box::new(fallback_err),
-                            })
-                        })
-                })
-            }
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::RustToolchain => "rust-toolchain",
+            Self::RustToolchainToml => "rust-toolchain.toml",
         }
     }
-}
-
-#[derive(Debug, thiserror::Error, PartialEq)]
-pub enum ParserError {
-    #[error("Failed to parse legacy toolchain-file variant: {0}")]
-    LegacyParseError(#[from] legacy::ParserError),
-
-    #[error("Failed to parse TOML toolchain-file variant: {0}")]
-    TomlParseError(#[from] toml::ParserError),
 
-    #[error("Both original and fallback parse attempts failed: {0}")]
-    FallbackError(FallbackError),
+    /// The strategy rustup applies to a file with this name.
+    pub fn strategy(&self) -> ParseStrategy {
+        match self {
+            Self::RustToolchain => ParseStrategy::Fallback {
+                first: Variant::Toml,
+                fallback_to: Variant::Legacy,
+            },
+            Self::RustToolchainToml => ParseStrategy::Only(Variant::Toml),
+        }
+    }
 }
 
-#[derive(Debug, PartialEq, thiserror::Error)]
-#[error("Failed to parse: '{first}' and failed to fallback on '{fallback_to}'")]
-pub struct FallbackError {
-    first: Box<ParserError>,
-    fallback_to: Box<ParserError>,
+/// Option to determine whether only to parse one rust-toolchain variant (TOML, or legacy), or
+/// to fallback to another variant.
+#[derive(Debug, Copy, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "kind", content = "value", rename_all = "snake_case")]
+pub enum ParseStrategy {
+    Only(Variant),
+    Fallback {
+        first: Variant,
+        fallback_to: Variant,
+    },
+    /// Apply the rules rustup uses for a toolchain file with the given name.
+    ///
+    /// When parsing fails, and the content looks like it was meant for another variant, the
+    /// error includes a [`Hint`].
+    Auto(FileName),
 }
 
-impl FallbackError {
-    pub fn first(&self) -> &ParserError {
-        self.first.as_ref()
-    }
-
-    pub fn fallback_to(&self) -> &ParserError {
-        self.fallback_to.as_ref()
+impl ParseStrategy {
+    /// Select the [`ParseStrategy::Auto`] strategy for the file name of the given path, if it is a
+    /// toolchain file.
+    #[cfg(feature = "std")]
+    pub fn from_file_name(path: impl AsRef<std::path::Path>) -> Option<Self> {
+        FileName::from_path(path).map(Self::Auto)
     }
 }
@@ -0,0 +1,142 @@
+use crate::{
+    FileName, ParseOutcome, ReadError, ReadErrorKind, RustToolchainToml, ToolchainFile, Variant,
+    Warning,
+};
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+#[cfg(test)]
+mod tests;
+
+/// Migrate the `rust-toolchain` file in the given directory to a `rust-toolchain.toml` file.
+///
+/// The `rust-toolchain.toml` file is written first, after which the `rust-toolchain` file is removed.
+/// A `rust-toolchain` file which already holds TOML content is copied as is, so its comments and
+/// layout are kept.
+///
+/// A `rust-toolchain` file which looks like TOML, but was only accepted as a toolchain name, is
+/// most likely a broken TOML file, and is not migrated.
+///
+/// When both files exist, the `rust-toolchain` file is only removed if both specify the same
+/// toolchain. Otherwise, neither file is touched, and the conflict is reported, since rustup
+/// would use the `rust-toolchain` file.
+pub fn migrate(directory: impl AsRef<Path>) -> Result<Migration, MigrateError> {
+    let directory = directory.as_ref();
+    let legacy = directory.join(FileName::RustToolchain.as_str());
+    let toml = directory.join(FileName::RustToolchainToml.as_str());
+
+    match (legacy.is_file(), toml.is_file()) {
+        (false, false) => Err(MigrateError::NotFound(directory.to_path_buf())),
+        (false, true) => Ok(Migration::AlreadyMigrated),
+        (true, true) => {
+            let rust_toolchain = RustToolchainToml::from(read_legacy(&legacy)?.into_file());
+            let rust_toolchain_toml =
+                RustToolchainToml::from(ToolchainFile::from_path(&toml)?.into_file());
+
+            if rust_toolchain != rust_toolchain_toml {
+                return Ok(Migration::Conflict {
+                    rust_toolchain,
+                    rust_toolchain_toml,
+                });
+            }
+
+            remove(&legacy)?;
+
+            Ok(Migration::RemovedDuplicate)
+        }
+        (true, false) => {
+            let outcome = read_legacy(&legacy)?;
+            let variant = outcome.variant();
+
+            let content = match variant {
+                Variant::Toml => fs::read(&legacy).map_err(|err| ReadError {
+                    path: Some(legacy.clone()),
+                    kind: ReadErrorKind::Io(err),
+                })?,
+                Variant::Legacy => RustToolchainToml::from(outcome.into_file())
+                    .to_toml_string()
+                    .into_bytes(),
+            };
+
+            write_new(&toml, &content).map_err(|err| MigrateError::Write(toml.clone(), err))?;
+            remove(&legacy)?;
+
+            Ok(Migration::Migrated { from: variant })
+        }
+    }
+}
+
+/// Read the `rust-toolchain` file, unless it is a broken TOML file which was read as a toolchain name.
+fn read_legacy(path: &Path) -> Result<ParseOutcome, MigrateError> {
+    let outcome = ToolchainFile::from_path(path)?;
+
+    let looks_like_toml = match outcome.file() {
+        ToolchainFile::Legacy(file) => file
+            .spec()
+            .map_or(false, |spec| Variant::sniff(spec) == Some(Variant::Toml)),
+        ToolchainFile::Toml(_) => false,
+    };
+
+    if looks_like_toml || outcome.warnings().contains(&Warning::TomlReadAsLegacy) {
+        return Err(MigrateError::InvalidToml(path.to_path_buf()));
+    }
+
+    Ok(outcome)
+}
+
+/// Write the file, unless it already exists.
+fn write_new(path: &Path, content: &[u8]) -> io::Result<()> {
+    OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path)?
+        .write_all(content)
+}
+
+fn remove(path: &Path) -> Result<(), MigrateError> {
+    fs::remove_file(path).map_err(|err| MigrateError::Remove(path.to_path_buf(), err))
+}
+
+/// The result of a [`migrate`].
+#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "kind", content = "value", rename_all = "snake_case")]
+pub enum Migration {
+    /// The `rust-toolchain` file, which holds content of the given variant, was replaced by a
+    /// `rust-toolchain.toml` file.
+    Migrated { from: Variant },
+    /// Both files specified the same toolchain, and the `rust-toolchain` file was removed.
+    RemovedDuplicate,
+    /// Only a `rust-toolchain.toml` file exists, so there was nothing to migrate.
+    AlreadyMigrated,
+    /// Both files exist, and specify different toolchains. Neither file was changed.
+    Conflict {
+        rust_toolchain: RustToolchainToml,
+        rust_toolchain_toml: RustToolchainToml,
+    },
+}
+
+#[derive(Debug, thiserror::Error, serde::Serialize)]
+#[serde(tag = "kind", content = "value", rename_all = "snake_case")]
+pub enum MigrateError {
+    #[error("Unable to migrate toolchain file: no 'rust-toolchain' or 'rust-toolchain.toml' file found in '{}'", .0.display())]
+    NotFound(PathBuf),
+
+    #[error(transparent)]
+    Read(#[from] ReadError),
+
+    #[error("Unable to migrate toolchain file: '{}' looks like TOML, but is not a valid TOML toolchain file", .0.display())]
+    InvalidToml(PathBuf),
+
+    #[error("Unable to migrate toolchain file: failed to write '{}': {1}", .0.display())]
+    Write(
+        PathBuf,
+        #[serde(serialize_with = "crate::schema::serialize_io_error")] io::Error,
+    ),
+
+    #[error("Unable to migrate toolchain file: failed to remove '{}': {1}", .0.display())]
+    Remove(
+        PathBuf,
+        #[serde(serialize_with = "crate::schema::serialize_io_error")] io::Error,
+    ),
+}
@@ -0,0 +1,175 @@
+use crate::{migrate, MigrateError, Migration, RustToolchainToml, Variant};
+use std::fs;
+use std::path::{Path, PathBuf};
+use yare::parameterized;
+
+/// A directory which is removed when dropped.
+struct TempDir(PathBuf);
+
+impl TempDir {
+    fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!(
+            "rust-toolchain-file-migrate-{name}-{}",
+            std::process::id()
+        ));
+
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+
+        Self(path)
+    }
+
+    fn write(&self, file_name: &str, content: &str) {
+        fs::write(self.0.join(file_name), content).unwrap();
+    }
+
+    fn read(&self, file_name: &str) -> Option<String> {
+        fs::read_to_string(self.0.join(file_name)).ok()
+    }
+
+    fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+#[test]
+fn legacy_content() {
+    let dir = TempDir::new("legacy-content");
+    dir.write("rust-toolchain", "nightly-2020-07-10\n");
+
+    assert_eq!(
+        migrate(dir.path()).unwrap(),
+        Migration::Migrated {
+            from: Variant::Legacy
+        }
+    );
+    assert_eq!(dir.read("rust-toolchain"), None);
+    assert_eq!(
+        dir.read("rust-toolchain.toml").unwrap(),
+        "[toolchain]\nchannel = \"nightly-2020-07-10\"\n"
+    );
+}
+
+#[test]
+fn toml_content_is_kept_as_is() {
+    let content = "# pinned for the release\n[toolchain]\nchannel = \"1.70\"\n";
+
+    let dir = TempDir::new("toml-content");
+    dir.write("rust-toolchain", content);
+
+    assert_eq!(
+        migrate(dir.path()).unwrap(),
+        Migration::Migrated {
+            from: Variant::Toml
+        }
+    );
+    assert_eq!(dir.read("rust-toolchain"), None);
+    assert_eq!(dir.read("rust-toolchain.toml").unwrap(), content);
+}
+
+#[test]
+fn already_migrated() {
+    let dir = TempDir::new("already-migrated");
+    dir.write("rust-toolchain.toml", "[toolchain]\nchannel = \"stable\"\n");
+
+    assert_eq!(migrate(dir.path()).unwrap(), Migration::AlreadyMigrated);
+}
+
+#[test]
+fn both_files_agree() {
+    let dir = TempDir::new("both-agree");
+    dir.write("rust-toolchain", "stable");
+    dir.write("rust-toolchain.toml", "[toolchain]\nchannel = \"stable\"\n");
+
+    assert_eq!(migrate(dir.path()).unwrap(), Migration::RemovedDuplicate);
+    assert_eq!(dir.read("rust-toolchain"), None);
+    assert_eq!(
+        dir.read("rust-toolchain.toml").unwrap(),
+        "[toolchain]\nchannel = \"stable\"\n"
+    );
+}
+
+#[test]
+fn both_files_disagree() {
+    let dir = TempDir::new("both-disagree");
+    dir.write("rust-toolchain", "stable");
+    dir.write("rust-toolchain.toml", "[toolchain]\nchannel = \"beta\"\n");
+
+    let migration = migrate(dir.path()).unwrap();
+
+    let Migration::Conflict {
+        rust_toolchain,
+        rust_toolchain_toml,
+    } = migration
+    else {
+        panic!("expected a conflict, but got: {migration:?}");
+    };
+
+    let expected = |channel| RustToolchainToml::builder().with_channel(channel).build();
+    assert_eq!(Ok(rust_toolchain), expected("stable"));
+    assert_eq!(Ok(rust_toolchain_toml), expected("beta"));
+
+    assert_eq!(dir.read("rust-toolchain").unwrap(), "stable");
+    assert_eq!(
+        dir.read("rust-toolchain.toml").unwrap(),
+        "[toolchain]\nchannel = \"beta\"\n"
+    );
+}
+
+#[test]
+fn not_found() {
+    let dir = TempDir::new("not-found");
+
+    assert!(matches!(
+        migrate(dir.path()).unwrap_err(),
+        MigrateError::NotFound(path) if path == dir.path()
+    ));
+}
+
+#[test]
+fn invalid_file_is_kept() {
+    let dir = TempDir::new("invalid");
+    dir.write("rust-toolchain", "");
+
+    assert!(matches!(
+        migrate(dir.path()).unwrap_err(),
+        MigrateError::Read(_)
+    ));
+    assert_eq!(dir.read("rust-toolchain").unwrap(), "");
+    assert_eq!(dir.read("rust-toolchain.toml"), None);
+}
+
+#[parameterized(
+    key_without_table = { "broken-key", "toolchain = \"stable\"\n" },
+    table_on_one_line = { "broken-table", "[toolchain] channel = \"stable\"\n" },
+)]
+fn broken_toml_is_kept(name: &str, content: &str) {
+    let dir = TempDir::new(name);
+    dir.write("rust-toolchain", content);
+
+    assert!(matches!(
+        migrate(dir.path()).unwrap_err(),
+        MigrateError::InvalidToml(path) if path == dir.path().join("rust-toolchain")
+    ));
+    assert_eq!(dir.read("rust-toolchain").unwrap(), content);
+    assert_eq!(dir.read("rust-toolchain.toml"), None);
+}
+
+#[test]
+fn broken_toml_is_not_removed_as_duplicate() {
+    let dir = TempDir::new("broken-duplicate");
+    dir.write("rust-toolchain", "toolchain = \"stable\"\n");
+    dir.write("rust-toolchain.toml", "[toolchain]\nchannel = \"stable\"\n");
+
+    assert!(matches!(
+        migrate(dir.path()).unwrap_err(),
+        MigrateError::InvalidToml(_)
+    ));
+    assert!(dir.read("rust-toolchain").is_some());
+}
@@ -0,0 +1,377 @@
+use crate::rustup::{Feature, RustupVersion};
+use crate::{PortablePath, Warning};
+use alloc::string::ToString;
+use alloc::vec::Vec;
+
+#[cfg(test)]
+mod tests;
+
+/// Options which determine how strict the legacy, TOML and combined parsers are.
+///
+/// Three presets are available: [`ParserOptions::rustup_exact`], [`ParserOptions::lenient`] and
+/// [`ParserOptions::pedantic`]. Individual settings can be adjusted with the `with_*` methods.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ParserOptions {
+    encoding: Encoding,
+    unknown_keys: Level,
+    whitespace: Whitespace,
+    relative_paths: Level,
+    platform_specific_paths: Level,
+    path_with_spec_keys: Level,
+    legacy_grammar: Level,
+    warnings_as_errors: bool,
+    limits: Limits,
+    rustup_version: Option<RustupVersion>,
+}
+
+impl ParserOptions {
+    /// Accept exactly what rustup accepts.
+    ///
+    /// Rustup reads toolchain files as UTF-8, trims surrounding whitespace, ignores unknown keys, allows
+    /// relative paths and rejects a `path` which is combined with any of the other toolchain keys.
+    pub fn rustup_exact() -> Self {
+        Self {
+            encoding: Encoding::Utf8,
+            unknown_keys: Level::Allow,
+            whitespace: Whitespace::Trim,
+            relative_paths: Level::Allow,
+            platform_specific_paths: Level::Allow,
+            path_with_spec_keys: Level::Deny,
+            legacy_grammar: Level::Allow,
+            warnings_as_errors: false,
+            limits: Limits::unlimited(),
+            rustup_version: None,
+        }
+    }
+
+    /// Accept everything rustup accepts, and more.
+    ///
+    /// In addition to [`ParserOptions::rustup_exact`], a leading byte order mark and blank lines in
+    /// legacy toolchain files are ignored, and a `path` may be combined with other toolchain keys,
+    /// in which case the other keys are ignored.
+    pub fn lenient() -> Self {
+        Self {
+            whitespace: Whitespace::Lenient,
+            path_with_spec_keys: Level::Allow,
+            ..Self::rustup_exact()
+        }
+    }
+
+    /// Flag everything which is questionable, and reject it.
+    ///
+    /// Only US-ASCII content is accepted, as prescribed by the rustup book. Unknown keys are rejected,
+    /// while surrounding whitespace, relative paths, paths which can only be used on one platform and
+    /// legacy toolchain files which violate the toolchain name grammar produce warnings, which are
+    /// promoted to errors.
+    pub fn pedantic() -> Self {
+        Self {
+            encoding: Encoding::Ascii,
+            unknown_keys: Level::Deny,
+            whitespace: Whitespace::Strict,
+            relative_paths: Level::Warn,
+            platform_specific_paths: Level::Warn,
+            path_with_spec_keys: Level::Deny,
+            legacy_grammar: Level::Warn,
+            warnings_as_errors: true,
+            limits: Limits::unlimited(),
+            rustup_version: None,
+        }
+    }
+}
+
+impl Default for ParserOptions {
+    fn default() -> Self {
+        Self::rustup_exact()
+    }
+}
+
+impl ParserOptions {
+    /// Set the encoding which the content must adhere to.
+    pub fn with_encoding(mut self, encoding: Encoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    /// Set how unknown keys in a TOML toolchain file are reported.
+    pub fn with_unknown_keys(mut self, level: Level) -> Self {
+        self.unknown_keys = level;
+        self
+    }
+
+    /// Set how whitespace surrounding the content is treated.
+    pub fn with_whitespace(mut self, whitespace: Whitespace) -> Self {
+        self.whitespace = whitespace;
+        self
+    }
+
+    /// Set how a relative `path` in a TOML toolchain file is reported.
+    pub fn with_relative_paths(mut self, level: Level) -> Self {
+        self.relative_paths = level;
+        self
+    }
+
+    /// Set how a toolchain path which can only be used on POSIX, or only on Windows, is reported.
+    pub fn with_platform_specific_paths(mut self, level: Level) -> Self {
+        self.platform_specific_paths = level;
+        self
+    }
+
+    /// Set how a `path` combined with `channel`, `components`, `targets` or `profile` is reported.
+    pub fn with_path_with_spec_keys(mut self, level: Level) -> Self {
+        self.path_with_spec_keys = level;
+        self
+    }
+
+    /// Set how the content of a legacy toolchain file which is not a valid toolchain name or path
+    /// is reported (see [`legacy::validate`](crate::legacy::validate)).
+    pub fn with_legacy_grammar(mut self, level: Level) -> Self {
+        self.legacy_grammar = level;
+        self
+    }
+
+    /// When set, every warning is promoted to an error.
+    pub fn with_warnings_as_errors(mut self, promote: bool) -> Self {
+        self.warnings_as_errors = promote;
+        self
+    }
+
+    /// Reject features of toolchain files which the given rustup release does not support.
+    pub fn with_rustup_version(mut self, version: RustupVersion) -> Self {
+        self.rustup_version = Some(version);
+        self
+    }
+
+    /// Set the limits on the size and complexity of the content.
+    pub fn with_limits(mut self, limits: Limits) -> Self {
+        self.limits = limits;
+        self
+    }
+}
+
+impl ParserOptions {
+    pub fn encoding(&self) -> Encoding {
+        self.encoding
+    }
+
+    pub fn unknown_keys(&self) -> Level {
+        self.unknown_keys
+    }
+
+    pub fn whitespace(&self) -> Whitespace {
+        self.whitespace
+    }
+
+    pub fn relative_paths(&self) -> Level {
+        self.relative_paths
+    }
+
+    pub fn platform_specific_paths(&self) -> Level {
+        self.platform_specific_paths
+    }
+
+    pub fn path_with_spec_keys(&self) -> Level {
+        self.path_with_spec_keys
+    }
+
+    pub fn legacy_grammar(&self) -> Level {
+        self.legacy_grammar
+    }
+
+    pub fn warnings_as_errors(&self) -> bool {
+        self.warnings_as_errors
+    }
+
+    pub fn limits(&self) -> Limits {
+        self.limits
+    }
+
+    /// The rustup release with which toolchain files must be compatible, if any.
+    pub fn rustup_version(&self) -> Option<RustupVersion> {
+        self.rustup_version
+    }
+
+    /// Report a finding at the given level.
+    ///
+    /// Returns the warning as an error if it must be rejected, and collects it otherwise.
+    pub(crate) fn report(
+        &self,
+        level: Level,
+        warning: Warning,
+        warnings: &mut Vec<Warning>,
+    ) -> Result<(), Warning> {
+        match level {
+            Level::Allow => Ok(()),
+            Level::Warn if !self.warnings_as_errors => {
+                warnings.push(warning);
+                Ok(())
+            }
+            Level::Warn | Level::Deny => Err(warning),
+        }
+    }
+
+    /// Report a toolchain path which can only be used on one platform, if required.
+    pub(crate) fn check_platform(
+        &self,
+        path: &PortablePath,
+        warnings: &mut Vec<Warning>,
+    ) -> Result<(), Warning> {
+        match path.style().platform() {
+            Some(platform) => self.report(
+                self.platform_specific_paths,
+                Warning::PlatformSpecificPath {
+                    path: path.to_string(),
+                    platform,
+                },
+                warnings,
+            ),
+            None => Ok(()),
+        }
+    }
+
+    /// Reject the first feature which is not supported by the selected rustup release, if any.
+    pub(crate) fn check_rustup_features(&self, features: &[Feature]) -> Result<(), Warning> {
+        let Some(version) = self.rustup_version else {
+            return Ok(());
+        };
+
+        match features.iter().find(|feature| feature.since() > version) {
+            Some(&feature) => Err(Warning::UnsupportedFeature { feature, version }),
+            None => Ok(()),
+        }
+    }
+
+    /// Verify the surrounding whitespace of the content, if required.
+    pub(crate) fn check_whitespace(
+        &self,
+        content: &[u8],
+        warnings: &mut Vec<Warning>,
+    ) -> Result<(), Warning> {
+        if self.whitespace == Whitespace::Strict && has_surrounding_whitespace(content) {
+            return self.report(Level::Warn, Warning::SurroundingWhitespace, warnings);
+        }
+
+        Ok(())
+    }
+}
+
+/// The encoding which the content of a toolchain file must adhere to.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Encoding {
+    /// Only accept US-ASCII encoded content.
+    Ascii,
+    /// Accept any UTF-8 encoded content.
+    Utf8,
+}
+
+/// How a questionable, but not necessarily invalid, finding is reported.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Level {
+    /// Accept silently.
+    Allow,
+    /// Accept, but produce a warning.
+    Warn,
+    /// Reject with an error.
+    Deny,
+}
+
+/// How whitespace surrounding the content of a toolchain file is treated.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Whitespace {
+    /// Trim surrounding whitespace, but produce a warning for anything besides a single trailing newline.
+    Strict,
+    /// Trim surrounding whitespace, like rustup.
+    Trim,
+    /// Trim surrounding whitespace and a leading byte order mark, and skip blank lines in legacy files.
+    Lenient,
+}
+
+/// Limits on the size and complexity of the content, for parsing untrusted toolchain files.
+///
+/// The presets of [`ParserOptions`] are unlimited, like rustup. [`Limits::default`] provides limits
+/// which are generous for any real-world toolchain file.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Limits {
+    max_bytes: usize,
+    max_items: usize,
+    max_string_len: usize,
+    max_nesting: usize,
+}
+
+impl Limits {
+    /// Do not limit the content.
+    pub fn unlimited() -> Self {
+        Self {
+            max_bytes: usize::MAX,
+            max_items: usize::MAX,
+            max_string_len: usize::MAX,
+            max_nesting: usize::MAX,
+        }
+    }
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            max_bytes: 64 * 1024,
+            max_items: 256,
+            max_string_len: 1024,
+            max_nesting: 16,
+        }
+    }
+}
+
+impl Limits {
+    /// Set the maximum size of the content, in bytes.
+    pub fn with_max_bytes(mut self, max: usize) -> Self {
+        self.max_bytes = max;
+        self
+    }
+
+    /// Set the maximum number of components, and the maximum number of targets.
+    pub fn with_max_items(mut self, max: usize) -> Self {
+        self.max_items = max;
+        self
+    }
+
+    /// Set the maximum length of a channel, path, profile, component or target, in bytes.
+    pub fn with_max_string_len(mut self, max: usize) -> Self {
+        self.max_string_len = max;
+        self
+    }
+
+    /// Set the maximum depth of nested arrays, inline tables and tables.
+    pub fn with_max_nesting(mut self, max: usize) -> Self {
+        self.max_nesting = max;
+        self
+    }
+
+    pub fn max_bytes(&self) -> usize {
+        self.max_bytes
+    }
+
+    pub fn max_items(&self) -> usize {
+        self.max_items
+    }
+
+    pub fn max_string_len(&self) -> usize {
+        self.max_string_len
+    }
+
+    pub fn max_nesting(&self) -> usize {
+        self.max_nesting
+    }
+}
+
+fn has_surrounding_whitespace(content: &[u8]) -> bool {
+    let content = content
+        .strip_suffix(b"\r\n")
+        .or_else(|| content.strip_suffix(b"\n"))
+        .unwrap_or(content);
+
+    let is_whitespace = |b: &u8| b.is_ascii_whitespace();
+
+    content.first().map_or(false, is_whitespace) || content.last().map_or(false, is_whitespace)
+}
@@ -0,0 +1,164 @@
+use crate::options::{Encoding, Level, Whitespace};
+use crate::rustup::RustupVersion;
+use crate::{ParserOptions, Warning};
+use yare::parameterized;
+
+#[test]
+fn default_is_rustup_exact() {
+    assert_eq!(ParserOptions::default(), ParserOptions::rustup_exact());
+}
+
+#[test]
+fn rustup_version() {
+    let version = RustupVersion::new(1, 23, 0);
+
+    assert_eq!(ParserOptions::default().rustup_version(), None);
+    assert_eq!(
+        ParserOptions::default()
+            .with_rustup_version(version)
+            .rustup_version(),
+        Some(version)
+    );
+}
+
+#[test]
+fn rustup_exact() {
+    let options = ParserOptions::rustup_exact();
+
+    assert_eq!(options.encoding(), Encoding::Utf8);
+    assert_eq!(options.unknown_keys(), Level::Allow);
+    assert_eq!(options.whitespace(), Whitespace::Trim);
+    assert_eq!(options.relative_paths(), Level::Allow);
+    assert_eq!(options.platform_specific_paths(), Level::Allow);
+    assert_eq!(options.path_with_spec_keys(), Level::Deny);
+    assert_eq!(options.legacy_grammar(), Level::Allow);
+    assert!(!options.warnings_as_errors());
+}
+
+#[test]
+fn lenient() {
+    let options = ParserOptions::lenient();
+
+    assert_eq!(options.encoding(), Encoding::Utf8);
+    assert_eq!(options.unknown_keys(), Level::Allow);
+    assert_eq!(options.whitespace(), Whitespace::Lenient);
+    assert_eq!(options.relative_paths(), Level::Allow);
+    assert_eq!(options.platform_specific_paths(), Level::Allow);
+    assert_eq!(options.path_with_spec_keys(), Level::Allow);
+    assert_eq!(options.legacy_grammar(), Level::Allow);
+    assert!(!options.warnings_as_errors());
+}
+
+#[test]
+fn pedantic() {
+    let options = ParserOptions::pedantic();
+
+    assert_eq!(options.encoding(), Encoding::Ascii);
+    assert_eq!(options.unknown_keys(), Level::Deny);
+    assert_eq!(options.whitespace(), Whitespace::Strict);
+    assert_eq!(options.relative_paths(), Level::Warn);
+    assert_eq!(options.platform_specific_paths(), Level::Warn);
+    assert_eq!(options.path_with_spec_keys(), Level::Deny);
+    assert_eq!(options.legacy_grammar(), Level::Warn);
+    assert!(options.warnings_as_errors());
+}
+
+#[test]
+fn setters() {
+    let options = ParserOptions::rustup_exact()
+        .with_encoding(Encoding::Ascii)
+        .with_unknown_keys(Level::Warn)
+        .with_whitespace(Whitespace::Strict)
+        .with_relative_paths(Level::Deny)
+        .with_platform_specific_paths(Level::Warn)
+        .with_path_with_spec_keys(Level::Allow)
+        .with_legacy_grammar(Level::Deny)
+        .with_warnings_as_errors(true);
+
+    assert_eq!(options.encoding(), Encoding::Ascii);
+    assert_eq!(options.unknown_keys(), Level::Warn);
+    assert_eq!(options.whitespace(), Whitespace::Strict);
+    assert_eq!(options.relative_paths(), Level::Deny);
+    assert_eq!(options.platform_specific_paths(), Level::Warn);
+    assert_eq!(options.path_with_spec_keys(), Level::Allow);
+    assert_eq!(options.legacy_grammar(), Level::Deny);
+    assert!(options.warnings_as_errors());
+}
+
+#[parameterized(
+    allow = { Level::Allow, false, Ok(()), 0 },
+    warn = { Level::Warn, false, Ok(()), 1 },
+    warn_promoted = { Level::Warn, true, Err(Warning::SurroundingWhitespace), 0 },
+    deny = { Level::Deny, false, Err(Warning::SurroundingWhitespace), 0 },
+)]
+fn report(level: Level, promote: bool, expected: Result<(), Warning>, collected: usize) {
+    let options = ParserOptions::rustup_exact().with_warnings_as_errors(promote);
+    let mut warnings = Vec::new();
+
+    let result = options.report(level, Warning::SurroundingWhitespace, &mut warnings);
+
+    assert_eq!(result, expected);
+    assert_eq!(warnings.len(), collected);
+}
+
+#[parameterized(
+    none = { "stable", false },
+    trailing_newline = { "stable\n", false },
+    trailing_crlf = { "stable\r\n", false },
+    trailing_newlines = { "stable\n\n", true },
+    trailing_space = { "stable ", true },
+    leading_space = { " stable", true },
+    leading_newline = { "\nstable\n", true },
+)]
+fn check_whitespace_strict(content: &str, expect_warning: bool) {
+    let options = ParserOptions::rustup_exact().with_whitespace(Whitespace::Strict);
+    let mut warnings = Vec::new();
+
+    let result = options.check_whitespace(content.as_bytes(), &mut warnings);
+
+    assert!(result.is_ok());
+    assert_eq!(!warnings.is_empty(), expect_warning);
+}
+
+#[parameterized(
+    trim = { Whitespace::Trim },
+    lenient = { Whitespace::Lenient },
+)]
+fn check_whitespace_not_strict(whitespace: Whitespace) {
+    let options = ParserOptions::rustup_exact().with_whitespace(whitespace);
+    let mut warnings = Vec::new();
+
+    let result = options.check_whitespace(b"  stable \n\n", &mut warnings);
+
+    assert!(result.is_ok());
+    assert!(warnings.is_empty());
+}
+
+mod limits {
+    use crate::options::Limits;
+    use crate::ParserOptions;
+
+    #[test]
+    fn presets_are_unlimited() {
+        assert_eq!(ParserOptions::rustup_exact().limits(), Limits::unlimited());
+        assert_eq!(ParserOptions::lenient().limits(), Limits::unlimited());
+        assert_eq!(ParserOptions::pedantic().limits(), Limits::unlimited());
+    }
+
+    #[test]
+    fn setters() {
+        let limits = Limits::default()
+            .with_max_bytes(1)
+            .with_max_items(2)
+            .with_max_string_len(3)
+            .with_max_nesting(4);
+
+        assert_eq!(limits.max_bytes(), 1);
+        assert_eq!(limits.max_items(), 2);
+        assert_eq!(limits.max_string_len(), 3);
+        assert_eq!(limits.max_nesting(), 4);
+
+        let options = ParserOptions::default().with_limits(limits);
+        assert_eq!(options.limits(), limits);
+    }
+}
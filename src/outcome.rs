@@ -0,0 +1,72 @@
+use crate::rustup::{self, Feature, RustupVersion};
+use crate::{FileName, ParserError, ToolchainFile, Variant, Warning};
+use std::path::{Path, PathBuf};
+
+/// The result of a successful parse by the combined [`Parser`](crate::Parser), including how the
+/// toolchain file was parsed.
+#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ParseOutcome {
+    pub(crate) file: ToolchainFile,
+    pub(crate) variant: Variant,
+    pub(crate) attempts: Vec<Variant>,
+    pub(crate) discarded: Vec<ParserError>,
+    pub(crate) warnings: Vec<Warning>,
+    pub(crate) path: Option<PathBuf>,
+}
+
+impl ParseOutcome {
+    pub fn file(&self) -> &ToolchainFile {
+        &self.file
+    }
+
+    pub fn into_file(self) -> ToolchainFile {
+        self.file
+    }
+
+    /// The variant which was successfully parsed.
+    pub fn variant(&self) -> Variant {
+        self.variant
+    }
+
+    /// The variants which were attempted, in order. The last attempt is the one which succeeded.
+    pub fn attempts(&self) -> &[Variant] {
+        &self.attempts
+    }
+
+    /// The errors of the attempts which failed, before an attempt succeeded.
+    pub fn discarded(&self) -> &[ParserError] {
+        &self.discarded
+    }
+
+    /// Whether the parser had to fall back to another variant.
+    pub fn is_fallback(&self) -> bool {
+        !self.discarded.is_empty()
+    }
+
+    /// Warnings which were not promoted to errors.
+    pub fn warnings(&self) -> &[Warning] {
+        &self.warnings
+    }
+
+    /// The path of the toolchain file, if it was read from a path.
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// The features of toolchain files used by the file, including its file name if it was read from
+    /// a path.
+    pub fn rustup_features(&self) -> Vec<Feature> {
+        let mut features = self.file.rustup_features();
+
+        if let Some(file_name) = self.path.as_deref().and_then(FileName::from_path) {
+            features.extend(file_name.rustup_features());
+        }
+
+        features
+    }
+
+    /// The minimum rustup release which honors every feature used by the file.
+    pub fn min_rustup_version(&self) -> RustupVersion {
+        rustup::min_version(&self.rustup_features())
+    }
+}
@@ -0,0 +1,234 @@
+use crate::options::Level;
+use crate::{
+    legacy, toml, FileName, ParseOutcome, ParseStrategy, ParserOptions, ToolchainFile, Variant,
+    Warning,
+};
+
+/// A combined parser for the legacy and TOML toolchain file formats.
+pub struct Parser<'content> {
+    pub(crate) content: &'content [u8],
+
+    pub(crate) parse_option: ParseStrategy,
+
+    pub(crate) options: ParserOptions,
+}
+
+impl<'content> Parser<'content> {
+    pub fn new(content: &'content str, parse_option: ParseStrategy) -> Self {
+        Self::from_slice(content.as_bytes(), parse_option)
+    }
+
+    /// Initialize a parser for content which has not been verified to be valid UTF-8.
+    pub fn from_slice(content: &'content [u8], parse_option: ParseStrategy) -> Self {
+        Self {
+            content,
+            parse_option,
+            options: ParserOptions::default(),
+        }
+    }
+
+    /// Replace the options used by this parser, which are passed on to the legacy and TOML parsers.
+    pub fn with_options(mut self, options: ParserOptions) -> Self {
+        self.options = options;
+        self
+    }
+}
+
+impl Parser<'_> {
+    pub fn parse(&self) -> Result<ToolchainFile, ParserError> {
+        self.parse_outcome().map(ParseOutcome::into_file)
+    }
+
+    /// Parse the content, and report how the toolchain file was parsed, alongside the parsed file.
+    ///
+    /// Unlike [`Parser::parse`], the errors of failed attempts which preceded a successful attempt are
+    /// not thrown away, and warnings which were not promoted to errors are collected.
+    pub fn parse_outcome(&self) -> Result<ParseOutcome, ParserError> {
+        self.parse_with_strategy(self.parse_option)
+    }
+
+    fn parse_with_strategy(&self, strategy: ParseStrategy) -> Result<ParseOutcome, ParserError> {
+        match strategy {
+            ParseStrategy::Only(variant) => {
+                let (file, warnings) = variant.parse_with(self.content, self.options)?;
+
+                Ok(ParseOutcome {
+                    file,
+                    variant,
+                    attempts: vec![variant],
+                    discarded: Vec::new(),
+                    warnings,
+                    path: None,
+                })
+            }
+            ParseStrategy::Fallback { first, fallback_to } => {
+                let original_err = match first.parse_with(self.content, self.options) {
+                    Ok((file, warnings)) => {
+                        return Ok(ParseOutcome {
+                            file,
+                            variant: first,
+                            attempts: vec![first],
+                            discarded: Vec::new(),
+                            warnings,
+                            path: None,
+                        })
+                    }
+                    Err(err) => err,
+                };
+
+                let (file, mut warnings) = match fallback_to.parse_with(self.content, self.options)
+                {
+                    Ok(parsed) => parsed,
+                    Err(fallback_err) => {
+                        return Err(ParserError::FallbackError(FallbackError {
+                            first: Box::new(original_err),
+                            fallback_to: Box::new(fallback_err),
+                        }))
+                    }
+                };
+
+                // Content which looks like TOML, but was only accepted as a legacy channel name, is
+                // most likely a broken TOML file.
+                if fallback_to == Variant::Legacy
+                    && std::str::from_utf8(self.content).map_or(false, |content| {
+                        Variant::sniff(content) == Some(Variant::Toml)
+                    })
+                {
+                    self.options
+                        .report(Level::Warn, Warning::TomlReadAsLegacy, &mut warnings)
+                        .map_err(ParserError::Denied)?;
+                }
+
+                Ok(ParseOutcome {
+                    file,
+                    variant: fallback_to,
+                    attempts: vec![first, fallback_to],
+                    discarded: vec![original_err],
+                    warnings,
+                    path: None,
+                })
+            }
+            ParseStrategy::Auto(file_name) => {
+                let outcome = self
+                    .parse_with_strategy(file_name.strategy())
+                    .map_err(|error| match file_name.hint(self.content) {
+                        Some(hint) => ParserError::HintedError(HintedError {
+                            error: Box::new(error),
+                            hint,
+                        }),
+                        None => error,
+                    })?;
+
+                self.options
+                    .check_rustup_features(&file_name.rustup_features())
+                    .map_err(ParserError::Denied)?;
+
+                Ok(outcome)
+            }
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "kind", content = "value", rename_all = "snake_case")]
+pub enum ParserError {
+    #[error("Failed to parse legacy toolchain-file variant: {0}")]
+    LegacyParseError(#[from] legacy::ParserError),
+
+    #[error("Failed to parse TOML toolchain-file variant: {0}")]
+    TomlParseError(#[from] toml::ParserError),
+
+    #[error("Both original and fallback parse attempts failed: {0}")]
+    FallbackError(FallbackError),
+
+    #[error("{0}")]
+    HintedError(HintedError),
+
+    #[error("Rejected toolchain file: {0}")]
+    Denied(Warning),
+}
+
+#[derive(Debug, PartialEq, thiserror::Error, serde::Serialize, serde::Deserialize)]
+#[error("Failed to parse: '{first}' and failed to fallback on '{fallback_to}'")]
+pub struct FallbackError {
+    first: Box<ParserError>,
+    fallback_to: Box<ParserError>,
+}
+
+impl FallbackError {
+    pub fn first(&self) -> &ParserError {
+        self.first.as_ref()
+    }
+
+    pub fn fallback_to(&self) -> &ParserError {
+        self.fallback_to.as_ref()
+    }
+}
+
+#[derive(Debug, PartialEq, thiserror::Error, serde::Serialize, serde::Deserialize)]
+#[error("{error} (hint: {hint})")]
+pub struct HintedError {
+    error: Box<ParserError>,
+    hint: Hint,
+}
+
+impl HintedError {
+    pub fn error(&self) -> &ParserError {
+        self.error.as_ref()
+    }
+
+    pub fn hint(&self) -> Hint {
+        self.hint
+    }
+}
+
+/// A likely explanation for a failure to parse a toolchain file.
+#[derive(
+    Debug, Copy, Clone, PartialEq, Eq, thiserror::Error, serde::Serialize, serde::Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum Hint {
+    #[error("'rust-toolchain.toml' only supports the TOML format; to use a single toolchain name, rename the file to 'rust-toolchain', or specify it as `channel` in the `[toolchain]` table")]
+    LegacyContentInTomlFile,
+
+    #[error("the content looks like TOML, so the TOML parse error is likely the relevant one")]
+    InvalidToml,
+}
+
+impl Variant {
+    fn parse_with(
+        &self,
+        content: &[u8],
+        options: ParserOptions,
+    ) -> Result<(ToolchainFile, Vec<Warning>), ParserError> {
+        match *self {
+            Self::Legacy => std::str::from_utf8(content)
+                .map_err(|_| legacy::ParserError::InvalidUtf8)
+                .and_then(|content| {
+                    legacy::Parser::new(content)
+                        .with_options(options)
+                        .parse_with_warnings()
+                })
+                .map(|(file, warnings)| (ToolchainFile::Legacy(file), warnings))
+                .map_err(From::from),
+            Self::Toml => toml::Parser::from_slice(content)
+                .with_options(options)
+                .parse_with_warnings()
+                .map(|(file, warnings)| (ToolchainFile::Toml(file), warnings))
+                .map_err(From::from),
+        }
+    }
+}
+
+impl FileName {
+    /// Explain a failure to parse the given content, if its contents do not fit the file name.
+    fn hint(&self, content: &[u8]) -> Option<Hint> {
+        let content = std::str::from_utf8(content).ok()?;
+
+        match (self, Variant::sniff(content)?) {
+            (Self::RustToolchainToml, Variant::Legacy) => Some(Hint::LegacyContentInTomlFile),
+            (Self::RustToolchain, Variant::Toml) => Some(Hint::InvalidToml),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,262 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+#[cfg(feature = "std")]
+use {
+    crate::Warning,
+    camino::{Utf8Path, Utf8PathBuf},
+};
+
+#[cfg(all(test, feature = "std"))]
+mod tests;
+
+/// A toolchain path, as written in a toolchain file.
+///
+/// The path is kept as a string, so it can be represented independently of the platform, and
+/// without the standard library.
+#[derive(
+    Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, serde::Serialize, serde::Deserialize,
+)]
+#[serde(transparent)]
+pub struct PortablePath(String);
+
+impl PortablePath {
+    pub fn new(path: impl Into<String>) -> Self {
+        Self(path.into())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn into_string(self) -> String {
+        self.0
+    }
+
+    /// Classify the path by the platform on which it is absolute, regardless of the host platform.
+    pub fn style(&self) -> PathStyle {
+        match self.0.as_bytes() {
+            [b'\\', b'\\', ..] => PathStyle::WindowsUnc,
+            [drive, b':', b'\\' | b'/', ..] if drive.is_ascii_alphabetic() => {
+                PathStyle::WindowsDrive
+            }
+            [b'/', ..] => PathStyle::PosixAbsolute,
+            _ => PathStyle::Relative,
+        }
+    }
+
+    /// Whether the path is absolute on either POSIX or Windows, regardless of the host platform.
+    pub fn is_absolute(&self) -> bool {
+        self.style() != PathStyle::Relative
+    }
+
+    pub fn is_relative(&self) -> bool {
+        !self.is_absolute()
+    }
+
+    /// Resolve the `.` and `..` segments of the path lexically, and separate segments by slashes.
+    ///
+    /// Backslashes separate segments too, unless the path is a POSIX path.
+    pub(crate) fn normalized(&self) -> String {
+        let style = self.style();
+        let path = self.as_str();
+
+        let (prefix, rest) = match style {
+            PathStyle::PosixAbsolute => ("/", &path[1..]),
+            PathStyle::WindowsUnc => ("//", &path[2..]),
+            PathStyle::WindowsDrive => (&path[..3], &path[3..]),
+            PathStyle::Relative => ("", path),
+        };
+
+        let is_separator = |c: char| c == '/' || (style != PathStyle::PosixAbsolute && c == '\\');
+
+        let mut segments: Vec<&str> = Vec::new();
+
+        for segment in rest.split(is_separator) {
+            match segment {
+                "" | "." => {}
+                ".." => match segments.last() {
+                    Some(last) if *last != ".." => {
+                        segments.pop();
+                    }
+                    // The parent of the root is the root itself.
+                    _ if !prefix.is_empty() => {}
+                    _ => segments.push(".."),
+                },
+                segment => segments.push(segment),
+            }
+        }
+
+        let mut normalized = String::from(prefix).replace('\\', "/");
+        normalized.push_str(&segments.join("/"));
+        normalized
+    }
+
+    #[cfg(feature = "std")]
+    pub fn as_path(&self) -> &std::path::Path {
+        std::path::Path::new(&self.0)
+    }
+
+    #[cfg(feature = "std")]
+    pub fn as_utf8_path(&self) -> &camino::Utf8Path {
+        camino::Utf8Path::new(&self.0)
+    }
+}
+
+/// The form of a toolchain path, which determines on which platform it can be used.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PathStyle {
+    /// A relative path, like `toolchains/rust`, which can be used on any platform.
+    Relative,
+    /// An absolute POSIX path, like `/opt/rust`.
+    PosixAbsolute,
+    /// A Windows path starting with a drive letter, like `C:\toolchains\rust`.
+    WindowsDrive,
+    /// A Windows UNC path, like `\\server\share\rust`.
+    WindowsUnc,
+}
+
+impl PathStyle {
+    /// The only platform on which a path of this style can be used, or `None` if it can be used on
+    /// any platform.
+    pub fn platform(&self) -> Option<Platform> {
+        match self {
+            Self::Relative => None,
+            Self::PosixAbsolute => Some(Platform::Posix),
+            Self::WindowsDrive | Self::WindowsUnc => Some(Platform::Windows),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Platform {
+    Posix,
+    Windows,
+}
+
+impl fmt::Display for Platform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Posix => f.write_str("POSIX"),
+            Self::Windows => f.write_str("Windows"),
+        }
+    }
+}
+
+impl From<String> for PortablePath {
+    fn from(path: String) -> Self {
+        Self(path)
+    }
+}
+
+impl From<&str> for PortablePath {
+    fn from(path: &str) -> Self {
+        Self(path.into())
+    }
+}
+
+impl AsRef<str> for PortablePath {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for PortablePath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// A toolchain path, resolved against the location of the toolchain file.
+#[cfg(feature = "std")]
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ResolvedPath {
+    path: Utf8PathBuf,
+    warnings: Vec<Warning>,
+}
+
+#[cfg(feature = "std")]
+impl ResolvedPath {
+    pub fn path(&self) -> &Utf8Path {
+        &self.path
+    }
+
+    pub fn into_path(self) -> Utf8PathBuf {
+        self.path
+    }
+
+    /// Findings about the resolved path, such as a path which leaves the root.
+    pub fn warnings(&self) -> &[Warning] {
+        &self.warnings
+    }
+}
+
+/// Resolve a relative path against the directory which holds the toolchain file, like rustup.
+///
+/// Absolute paths are kept as is. In both cases, `.` and `..` segments are normalized lexically, and
+/// backslashes are read as separators, unless the path is a POSIX path (see [`PathStyle`]). The
+/// same rules apply to the location of the toolchain file and the root, regardless of the host, so
+/// the root defaults to the directory of the toolchain file.
+#[cfg(feature = "std")]
+pub(crate) fn resolve(
+    path: &PortablePath,
+    toolchain_file: &Utf8Path,
+    root: Option<&Utf8Path>,
+) -> Result<ResolvedPath, ResolveError> {
+    let location = PortablePath::from(toolchain_file.as_str());
+
+    if location.is_relative() {
+        return Err(ResolveError::RelativeLocation(toolchain_file.to_path_buf()));
+    }
+
+    // Every style separates segments by slashes, and the `..` segment leaves the file name.
+    let directory = PortablePath::new(alloc::format!("{location}/..")).normalized();
+
+    let root = match root {
+        Some(root) if PortablePath::from(root.as_str()).is_relative() => {
+            return Err(ResolveError::RelativeRoot(root.to_path_buf()));
+        }
+        Some(root) => PortablePath::from(root.as_str()).normalized(),
+        None => directory.clone(),
+    };
+
+    let mut warnings = Vec::new();
+
+    if path.is_absolute() {
+        return Ok(ResolvedPath {
+            path: Utf8PathBuf::from(path.normalized()),
+            warnings,
+        });
+    }
+
+    let resolved =
+        PortablePath::new(alloc::format!("{directory}/{}", path.normalized())).normalized();
+
+    let within_root = resolved.strip_prefix(&root).map_or(false, |rest| {
+        rest.is_empty() || root.ends_with('/') || rest.starts_with('/')
+    });
+
+    if !within_root {
+        warnings.push(Warning::PathOutsideRoot(path.to_string()));
+    }
+
+    Ok(ResolvedPath {
+        path: Utf8PathBuf::from(resolved),
+        warnings,
+    })
+}
+
+/// An error which occurred while resolving a toolchain path.
+#[cfg(feature = "std")]
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "kind", content = "value", rename_all = "snake_case")]
+pub enum ResolveError {
+    #[error("Unable to resolve toolchain path: the location of the toolchain file '{0}' is not absolute")]
+    RelativeLocation(Utf8PathBuf),
+
+    #[error("Unable to resolve toolchain path: the root '{0}' is not absolute")]
+    RelativeRoot(Utf8PathBuf),
+}
@@ -0,0 +1,70 @@
+use crate::path::{PathStyle, Platform};
+use crate::PortablePath;
+use yare::parameterized;
+
+#[test]
+fn as_str() {
+    let path = PortablePath::from("/my/toolchain");
+
+    assert_eq!(path.as_str(), "/my/toolchain");
+    assert_eq!(path.to_string(), "/my/toolchain");
+    assert_eq!(path.into_string(), "/my/toolchain");
+}
+
+#[parameterized(
+    relative = { "my/toolchain", false },
+    dot = { "./my/toolchain", false },
+    name = { "stable", false },
+    posix = { "/opt/rust", true },
+    windows_drive = { r"C:\toolchains\rust", true },
+    windows_unc = { r"\\server\share\rust", true },
+)]
+fn is_absolute(path: &str, expected: bool) {
+    let path = PortablePath::new(path);
+
+    assert_eq!(path.is_absolute(), expected);
+    assert_eq!(path.is_relative(), !expected);
+}
+
+#[test]
+fn as_path() {
+    let path = PortablePath::from("/my/toolchain");
+
+    assert_eq!(path.as_path(), std::path::Path::new("/my/toolchain"));
+    assert_eq!(path.as_utf8_path(), camino::Utf8Path::new("/my/toolchain"));
+}
+
+#[parameterized(
+    relative = { "my/toolchain", PathStyle::Relative, None },
+    windows_relative = { r"my\toolchain", PathStyle::Relative, None },
+    drive_relative = { "C:toolchain", PathStyle::Relative, None },
+    posix = { "/opt/rust", PathStyle::PosixAbsolute, Some(Platform::Posix) },
+    posix_double_slash = { "//opt/rust", PathStyle::PosixAbsolute, Some(Platform::Posix) },
+    windows_drive = { r"C:\toolchains\rust", PathStyle::WindowsDrive, Some(Platform::Windows) },
+    windows_drive_forward_slash = { "c:/toolchains/rust", PathStyle::WindowsDrive, Some(Platform::Windows) },
+    windows_unc = { r"\\server\share\rust", PathStyle::WindowsUnc, Some(Platform::Windows) },
+    windows_verbatim = { r"\\?\C:\rust", PathStyle::WindowsUnc, Some(Platform::Windows) },
+)]
+fn style(path: &str, style: PathStyle, platform: Option<Platform>) {
+    let path = PortablePath::new(path);
+
+    assert_eq!(path.style(), style);
+    assert_eq!(path.style().platform(), platform);
+}
+
+#[parameterized(
+    relative = { "toolchains/rust", "toolchains/rust" },
+    current_dir = { "./toolchains/./rust", "toolchains/rust" },
+    parent_dir = { "toolchains/../rust", "rust" },
+    leading_parent_dir = { "../../rust", "../../rust" },
+    trailing_separator = { "toolchains/rust/", "toolchains/rust" },
+    backslashes = { r"toolchains\rust", "toolchains/rust" },
+    posix = { "/opt//rust/../rust", "/opt/rust" },
+    posix_parent_of_root = { "/../opt/rust", "/opt/rust" },
+    posix_backslash = { r"/opt/ru\st", r"/opt/ru\st" },
+    windows_drive = { r"C:\toolchains\..\rust", "C:/rust" },
+    windows_unc = { r"\\server\share\rust", "//server/share/rust" },
+)]
+fn normalized(path: &str, expected: &str) {
+    assert_eq!(PortablePath::from(path).normalized(), expected);
+}
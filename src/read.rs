@@ -0,0 +1,142 @@
+use crate::{ParseOutcome, ParseStrategy, Parser, ParserError, ParserOptions, ToolchainFile};
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+#[cfg(test)]
+mod tests;
+
+impl ToolchainFile {
+    /// Read and parse the toolchain file at the given path.
+    ///
+    /// The variant is chosen from the file name, by applying rustup's rules (see [`ParseStrategy::Auto`]).
+    /// The path is kept on the returned outcome.
+    pub fn from_path(path: impl AsRef<Path>) -> Result<ParseOutcome, ReadError> {
+        Self::from_path_with_options(path, ParserOptions::default())
+    }
+
+    /// Read and parse the toolchain file at the given path, with the given parser options.
+    ///
+    /// A file larger than the size limit of the options is rejected, without reading more of it
+    /// than the limit.
+    pub fn from_path_with_options(
+        path: impl AsRef<Path>,
+        options: ParserOptions,
+    ) -> Result<ParseOutcome, ReadError> {
+        let path = path.as_ref();
+        let at_path = |kind| ReadError {
+            path: Some(path.to_path_buf()),
+            kind,
+        };
+
+        let strategy = ParseStrategy::from_file_name(path)
+            .ok_or_else(|| at_path(ReadErrorKind::UnrecognizedFileName))?;
+
+        let file = File::open(path).map_err(|err| at_path(ReadErrorKind::Io(err)))?;
+        let content = read_limited(file, &options).map_err(at_path)?;
+
+        let mut outcome = Parser::from_slice(&content, strategy)
+            .with_options(options)
+            .parse_outcome()
+            .map_err(|err| at_path(ReadErrorKind::Parse(err)))?;
+
+        outcome.path = Some(path.to_path_buf());
+
+        Ok(outcome)
+    }
+
+    /// Read the content from the given reader, and parse it with the given strategy.
+    pub fn from_reader(
+        reader: impl Read,
+        strategy: ParseStrategy,
+    ) -> Result<ParseOutcome, ReadError> {
+        Self::from_reader_with_options(reader, strategy, ParserOptions::default())
+    }
+
+    /// Read the content from the given reader, and parse it with the given strategy and parser
+    /// options.
+    ///
+    /// Content larger than the size limit of the options is rejected, without reading more of it
+    /// than the limit.
+    pub fn from_reader_with_options(
+        reader: impl Read,
+        strategy: ParseStrategy,
+        options: ParserOptions,
+    ) -> Result<ParseOutcome, ReadError> {
+        let content = read_limited(reader, &options)?;
+
+        Parser::from_slice(&content, strategy)
+            .with_options(options)
+            .parse_outcome()
+            .map_err(|err| ReadErrorKind::Parse(err).into())
+    }
+}
+
+/// Read the content, unless it exceeds the size limit of the options.
+fn read_limited(reader: impl Read, options: &ParserOptions) -> Result<Vec<u8>, ReadErrorKind> {
+    let max_bytes = options.limits().max_bytes();
+    let mut content = Vec::new();
+
+    reader
+        .take((max_bytes as u64).saturating_add(1))
+        .read_to_end(&mut content)
+        .map_err(ReadErrorKind::Io)?;
+
+    if content.len() > max_bytes {
+        return Err(ReadErrorKind::TooLarge(max_bytes));
+    }
+
+    Ok(content)
+}
+
+/// An error which occurred while reading a toolchain file.
+///
+/// The kind of error is part of the message, and is thus not reported as its source.
+#[derive(Debug, thiserror::Error, serde::Serialize)]
+pub struct ReadError {
+    pub(crate) path: Option<PathBuf>,
+    pub(crate) kind: ReadErrorKind,
+}
+
+impl ReadError {
+    /// The path of the toolchain file, if it was read from a path.
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    pub fn kind(&self) -> &ReadErrorKind {
+        &self.kind
+    }
+}
+
+impl From<ReadErrorKind> for ReadError {
+    fn from(kind: ReadErrorKind) -> Self {
+        Self { path: None, kind }
+    }
+}
+
+impl fmt::Display for ReadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.path {
+            Some(ref path) => write!(f, "{}: {}", path.display(), self.kind),
+            None => write!(f, "{}", self.kind),
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error, serde::Serialize)]
+#[serde(tag = "kind", content = "value", rename_all = "snake_case")]
+pub enum ReadErrorKind {
+    #[error("Unable to read toolchain file: {0}")]
+    Io(#[serde(serialize_with = "crate::schema::serialize_io_error")] io::Error),
+
+    #[error("{0}")]
+    Parse(ParserError),
+
+    #[error("Unable to read toolchain file: the file exceeds the limit of {0} bytes")]
+    TooLarge(usize),
+
+    #[error("Unable to determine the toolchain file variant, expected a file named 'rust-toolchain' or 'rust-toolchain.toml'")]
+    UnrecognizedFileName,
+}
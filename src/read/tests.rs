@@ -0,0 +1,132 @@
+use crate::options::Limits;
+use crate::{
+    FileName, ParseStrategy, ParserError, ParserOptions, ReadErrorKind, ToolchainFile, Variant,
+};
+use std::path::{Path, PathBuf};
+
+fn fixture(path: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(path)
+}
+
+mod from_path {
+    use super::*;
+    use yare::parameterized;
+
+    #[parameterized(
+        legacy_only = { "legacy-only/rust-toolchain", Variant::Legacy },
+        toml_without_ext = { "toml-without-ext/rust-toolchain", Variant::Toml },
+        toml_with_ext = { "rustup-book-layout/rust-toolchain.toml", Variant::Toml },
+        toml_local_path = { "rustup-book-local-toolchain/rust-toolchain.toml", Variant::Toml },
+    )]
+    fn accept(path: &str, expected: Variant) {
+        let path = fixture(path);
+
+        let outcome = ToolchainFile::from_path(&path).unwrap();
+        assert_eq!(outcome.variant(), expected);
+        assert_eq!(outcome.path(), Some(path.as_path()));
+    }
+
+    #[test]
+    fn reject_legacy_in_toml_file() {
+        let path = fixture("legacy-in-toml/rust-toolchain.toml");
+
+        let error = ToolchainFile::from_path(&path).unwrap_err();
+        assert_eq!(error.path(), Some(path.as_path()));
+        assert!(matches!(
+            error.kind(),
+            ReadErrorKind::Parse(ParserError::HintedError(_))
+        ));
+        assert!(error.to_string().starts_with(&path.display().to_string()));
+    }
+
+    #[test]
+    fn reject_unrecognized_file_name() {
+        let path = fixture("../../Cargo.toml");
+
+        let error = ToolchainFile::from_path(&path).unwrap_err();
+        assert_eq!(error.path(), Some(path.as_path()));
+        assert!(matches!(error.kind(), ReadErrorKind::UnrecognizedFileName));
+    }
+
+    #[test]
+    fn reject_missing_file() {
+        let path = fixture("missing/rust-toolchain");
+
+        let error = ToolchainFile::from_path(&path).unwrap_err();
+        assert_eq!(error.path(), Some(path.as_path()));
+        assert!(matches!(error.kind(), ReadErrorKind::Io(_)));
+    }
+
+    #[test]
+    fn reports_cause_once() {
+        let path = fixture("missing/rust-toolchain");
+
+        let error = ToolchainFile::from_path(&path).unwrap_err();
+        assert!(error.to_string().contains(&error.kind().to_string()));
+        assert!(std::error::Error::source(&error).is_none());
+    }
+
+    #[test]
+    fn reject_too_large() {
+        let path = fixture("rustup-book-layout/rust-toolchain.toml");
+        let options = ParserOptions::default().with_limits(Limits::default().with_max_bytes(8));
+
+        let error = ToolchainFile::from_path_with_options(&path, options).unwrap_err();
+        assert_eq!(error.path(), Some(path.as_path()));
+        assert!(matches!(error.kind(), ReadErrorKind::TooLarge(8)));
+    }
+}
+
+mod from_reader {
+    use super::*;
+
+    #[test]
+    fn accept() {
+        let content = b"[toolchain]\nchannel = \"stable\"\n";
+        let strategy = ParseStrategy::Auto(FileName::RustToolchainToml);
+
+        let outcome = ToolchainFile::from_reader(content.as_slice(), strategy).unwrap();
+        assert_eq!(outcome.variant(), Variant::Toml);
+        assert_eq!(outcome.path(), None);
+    }
+
+    #[test]
+    fn reject_invalid_utf8() {
+        let content = b"\xFF\xFE";
+        let strategy = ParseStrategy::Only(Variant::Legacy);
+
+        let error = ToolchainFile::from_reader(content.as_slice(), strategy).unwrap_err();
+        assert_eq!(error.path(), None);
+        assert!(matches!(
+            error.kind(),
+            ReadErrorKind::Parse(ParserError::LegacyParseError(
+                crate::legacy::ParserError::InvalidUtf8
+            ))
+        ));
+    }
+
+    #[test]
+    fn accept_within_limit() {
+        let content = b"stable";
+        let strategy = ParseStrategy::Only(Variant::Legacy);
+        let options = ParserOptions::default().with_limits(Limits::default().with_max_bytes(6));
+
+        let outcome =
+            ToolchainFile::from_reader_with_options(content.as_slice(), strategy, options).unwrap();
+        assert_eq!(outcome.variant(), Variant::Legacy);
+    }
+
+    #[test]
+    fn reject_too_large_without_reading_all() {
+        let strategy = ParseStrategy::Only(Variant::Legacy);
+        let options = ParserOptions::default().with_limits(Limits::default().with_max_bytes(1024));
+
+        let error =
+            ToolchainFile::from_reader_with_options(std::io::repeat(b'a'), strategy, options)
+                .unwrap_err();
+        assert_eq!(error.path(), None);
+        assert!(matches!(error.kind(), ReadErrorKind::TooLarge(1024)));
+    }
+}
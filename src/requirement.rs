@@ -0,0 +1,277 @@
+//! Requirements of a tool on the toolchain selected by a toolchain file, like a recent nightly
+//! toolchain with the `rust-src` component.
+
+use crate::toml::normalize::{channel_name, component_name};
+use crate::toml::{release, Component, Target, ToolchainSection, ToolchainSpec};
+use crate::{PortablePath, RustToolchainToml, ToolchainFile};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+#[cfg(test)]
+mod tests;
+
+/// The components included by the `minimal` profile, which are installed for every profile.
+const MINIMAL_COMPONENTS: [&str; 3] = ["cargo", "rust-std", "rustc"];
+
+/// The components included by the `default` profile, besides those of the `minimal` profile.
+const DEFAULT_COMPONENTS: [&str; 3] = ["clippy", "rust-docs", "rustfmt"];
+
+impl ToolchainFile {
+    /// The items of the requirement which the toolchain selected by this file does not meet, in the
+    /// order channel, components and targets. The requirement is met if none are returned.
+    ///
+    /// The components and targets of the file are normalized first (see
+    /// [`ToolchainSpec::normalize`]), but the channel is compared as written. Components included by
+    /// the profile of the file count as specified; without a profile, only the components of the
+    /// `minimal` profile do, since rustup falls back to the profile configured by the user. A file
+    /// which selects a local toolchain by its path can only meet an empty requirement.
+    pub fn satisfies(&self, requirement: &Requirement) -> Vec<Unmet> {
+        if requirement.is_empty() {
+            return Vec::new();
+        }
+
+        let file = RustToolchainToml::from(self.clone()).normalize_except_channel();
+
+        match file.toolchain() {
+            ToolchainSection::Path(path) => {
+                alloc::vec![Unmet::LocalToolchain(path.portable_path().clone())]
+            }
+            ToolchainSection::Spec(spec) => requirement.unmet(spec),
+        }
+    }
+}
+
+/// What a tool requires from a toolchain, see [`ToolchainFile::satisfies`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Requirement {
+    channel: Option<ChannelRequirement>,
+    components: Vec<String>,
+    targets: Vec<String>,
+}
+
+impl Requirement {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_channel(mut self, channel: ChannelRequirement) -> Self {
+        self.channel = Some(channel);
+        self
+    }
+
+    pub fn with_component(mut self, component: impl Into<String>) -> Self {
+        self.components.push(component.into());
+        self
+    }
+
+    pub fn with_components<I: IntoIterator<Item = S>, S: Into<String>>(
+        mut self,
+        components: I,
+    ) -> Self {
+        self.components
+            .extend(components.into_iter().map(Into::into));
+        self
+    }
+
+    pub fn with_target(mut self, target: impl Into<String>) -> Self {
+        self.targets.push(target.into());
+        self
+    }
+
+    pub fn with_targets<I: IntoIterator<Item = S>, S: Into<String>>(mut self, targets: I) -> Self {
+        self.targets.extend(targets.into_iter().map(Into::into));
+        self
+    }
+
+    pub fn channel(&self) -> Option<&ChannelRequirement> {
+        self.channel.as_ref()
+    }
+
+    pub fn components(&self) -> &[String] {
+        &self.components
+    }
+
+    pub fn targets(&self) -> &[String] {
+        &self.targets
+    }
+
+    /// Whether every toolchain meets the requirement.
+    pub fn is_empty(&self) -> bool {
+        self.channel.is_none() && self.components.is_empty() && self.targets.is_empty()
+    }
+
+    fn unmet(&self, spec: &ToolchainSpec) -> Vec<Unmet> {
+        let mut unmet = Vec::new();
+
+        let channel = spec.channel().map(|channel| channel.name());
+
+        if let Some(required) = &self.channel {
+            if !channel.map_or(false, |channel| required.is_met_by(channel)) {
+                unmet.push(Unmet::Channel {
+                    required: required.clone(),
+                    found: channel.map(ToString::to_string),
+                });
+            }
+        }
+
+        let profile = spec.profile().map(|profile| profile.name());
+        let components = spec.components().unwrap_or_default();
+
+        for component in &self.components {
+            let component = component_name(component);
+
+            let included = MINIMAL_COMPONENTS.contains(&component)
+                || match profile {
+                    Some("complete") => true,
+                    Some("default") => DEFAULT_COMPONENTS.contains(&component),
+                    _ => false,
+                };
+
+            if !included && !components.iter().any(|c| c.name() == component) {
+                push_once(
+                    &mut unmet,
+                    Unmet::Component(Component(component.to_string())),
+                );
+            }
+        }
+
+        let targets = spec.targets().unwrap_or_default();
+
+        for target in &self.targets {
+            if !targets.iter().any(|t| t.name() == target) {
+                push_once(&mut unmet, Unmet::Target(Target(target.clone())));
+            }
+        }
+
+        unmet
+    }
+}
+
+fn push_once(unmet: &mut Vec<Unmet>, item: Unmet) {
+    if !unmet.contains(&item) {
+        unmet.push(item);
+    }
+}
+
+/// A requirement on the channel of a toolchain.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "kind", content = "value", rename_all = "snake_case")]
+pub enum ChannelRequirement {
+    /// A nightly toolchain, released on or after the given date, like `2023-06-01`, if any.
+    ///
+    /// The undated `nightly` channel always meets the requirement, since it selects the latest
+    /// nightly toolchain.
+    Nightly { since: Option<String> },
+    /// A release with at least the given version, like `1.70.0`.
+    ///
+    /// A version channel without a patch version, like `1.72`, selects the latest patch release, and
+    /// thus meets the requirement for every patch release of it. The undated `stable`, `beta` and
+    /// `nightly` channels always meet the requirement, since they select the latest release of the
+    /// channel.
+    AtLeast(String),
+    /// Exactly the given channel, as written.
+    Exactly(String),
+}
+
+impl ChannelRequirement {
+    /// Whether the given channel meets the requirement.
+    pub fn is_met_by(&self, channel: &str) -> bool {
+        match self {
+            Self::Nightly { since } => {
+                if !is_channel(channel, "nightly") {
+                    return false;
+                }
+
+                match (since, release::date(channel, "nightly")) {
+                    (Some(since), Some(date)) => date >= since.as_str(),
+                    _ => true,
+                }
+            }
+            Self::AtLeast(version) => {
+                let required = release::version(&channel_name(version));
+
+                match (required, release::version(channel)) {
+                    (Some(required), Some(found)) => found >= required,
+                    (Some(_), None) => is_floating(channel),
+                    (None, _) => false,
+                }
+            }
+            Self::Exactly(expected) => expected == channel,
+        }
+    }
+}
+
+/// Whether the channel selects the latest release of the `stable`, `beta` or `nightly` channel.
+fn is_floating(channel: &str) -> bool {
+    ["stable", "beta", "nightly"]
+        .into_iter()
+        .any(|name| is_channel(channel, name) && release::date(channel, name).is_none())
+}
+
+/// Whether the channel is the given channel, optionally followed by a date and host.
+fn is_channel(channel: &str, name: &str) -> bool {
+    channel
+        .strip_prefix(name)
+        .map_or(false, |rest| rest.is_empty() || rest.starts_with('-'))
+}
+
+impl fmt::Display for ChannelRequirement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Nightly { since: None } => f.write_str("a nightly toolchain"),
+            Self::Nightly { since: Some(date) } => {
+                write!(f, "a nightly toolchain released on or after {date}")
+            }
+            Self::AtLeast(version) => write!(f, "a toolchain of version {version} or newer"),
+            Self::Exactly(channel) => write!(f, "the '{channel}' toolchain"),
+        }
+    }
+}
+
+/// An item of a [`Requirement`] which a toolchain file does not meet.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "kind", content = "value", rename_all = "snake_case")]
+pub enum Unmet {
+    /// The file selects a local toolchain by its path, of which the channel, components and targets
+    /// are unknown.
+    LocalToolchain(PortablePath),
+    /// The channel does not meet the requirement, or the file does not specify a channel.
+    Channel {
+        required: ChannelRequirement,
+        found: Option<String>,
+    },
+    /// The component is not specified, nor included by the profile.
+    Component(Component),
+    /// The target is not specified.
+    Target(Target),
+}
+
+impl fmt::Display for Unmet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::LocalToolchain(path) => write!(
+                f,
+                "the toolchain file selects the local toolchain '{path}', which can not be verified"
+            ),
+            Self::Channel {
+                required,
+                found: Some(found),
+            } => write!(f, "requires {required}, but the channel is '{found}'"),
+            Self::Channel {
+                required,
+                found: None,
+            } => write!(f, "requires {required}, but no channel is specified"),
+            Self::Component(component) => write!(
+                f,
+                "add '{}' to the components of the toolchain file",
+                component.name()
+            ),
+            Self::Target(target) => write!(
+                f,
+                "add '{}' to the targets of the toolchain file",
+                target.name()
+            ),
+        }
+    }
+}
@@ -0,0 +1,147 @@
+use crate::requirement::{ChannelRequirement, Requirement, Unmet};
+use crate::test_helpers::{legacy, toml};
+use crate::toml::{Component, Target};
+use crate::{PortablePath, RustToolchainToml};
+use yare::parameterized;
+
+fn nightly_since(date: &str) -> ChannelRequirement {
+    ChannelRequirement::Nightly {
+        since: Some(date.to_string()),
+    }
+}
+
+#[parameterized(
+    nightly = { ChannelRequirement::Nightly { since: None }, "nightly-2023-06-01" },
+    nightly_with_host = { ChannelRequirement::Nightly { since: None }, "nightly-x86_64-unknown-linux-gnu" },
+    nightly_on_date = { nightly_since("2023-06-01"), "nightly-2023-06-01" },
+    nightly_after_date = { nightly_since("2023-06-01"), "nightly-2023-07-15-x86_64-unknown-linux-gnu" },
+    latest_nightly = { nightly_since("2023-06-01"), "nightly" },
+    same_version = { ChannelRequirement::AtLeast("1.70.0".to_string()), "1.70.0" },
+    newer_version = { ChannelRequirement::AtLeast("1.70".to_string()), "1.72.1" },
+    latest_patch = { ChannelRequirement::AtLeast("1.72.1".to_string()), "1.72" },
+    latest_stable = { ChannelRequirement::AtLeast("1.70.0".to_string()), "stable" },
+    exactly = { ChannelRequirement::Exactly("1.70".to_string()), "1.70" },
+)]
+fn channel_met(requirement: ChannelRequirement, channel: &str) {
+    assert!(requirement.is_met_by(channel));
+}
+
+#[parameterized(
+    stable_for_nightly = { ChannelRequirement::Nightly { since: None }, "stable" },
+    nightly_before_date = { nightly_since("2023-06-01"), "nightly-2023-05-31" },
+    older_version = { ChannelRequirement::AtLeast("1.70.0".to_string()), "1.69.0" },
+    older_minor_version = { ChannelRequirement::AtLeast("1.73.0".to_string()), "1.72" },
+    dated_stable = { ChannelRequirement::AtLeast("1.70.0".to_string()), "stable-2023-06-01" },
+    invalid_version = { ChannelRequirement::AtLeast("latest".to_string()), "1.72.1" },
+    exactly_other = { ChannelRequirement::Exactly("1.70.0".to_string()), "1.70.1" },
+    exactly_completed = { ChannelRequirement::Exactly("1.70".to_string()), "1.70.0" },
+)]
+fn channel_unmet(requirement: ChannelRequirement, channel: &str) {
+    assert!(!requirement.is_met_by(channel));
+}
+
+#[test]
+fn satisfied() {
+    let file = toml(
+        RustToolchainToml::builder()
+            .with_channel("nightly-2023-07-01")
+            .with_components(["rust-src", "llvm-tools-preview"])
+            .with_target("wasm32-unknown-unknown"),
+    );
+    let requirement = Requirement::new()
+        .with_channel(nightly_since("2023-06-01"))
+        .with_components(["rust-src", "llvm-tools"])
+        .with_target("wasm32-unknown-unknown");
+
+    assert!(file.satisfies(&requirement).is_empty());
+}
+
+#[test]
+fn unmet_items() {
+    let file = legacy("stable");
+    let requirement = Requirement::new()
+        .with_channel(nightly_since("2023-06-01"))
+        .with_components(["rust-src", "llvm-tools-preview", "llvm-tools"])
+        .with_target("wasm32-unknown-unknown");
+
+    assert_eq!(
+        file.satisfies(&requirement),
+        vec![
+            Unmet::Channel {
+                required: nightly_since("2023-06-01"),
+                found: Some("stable".to_string()),
+            },
+            Unmet::Component(Component("rust-src".to_string())),
+            Unmet::Component(Component("llvm-tools".to_string())),
+            Unmet::Target(Target("wasm32-unknown-unknown".to_string())),
+        ]
+    );
+}
+
+#[test]
+fn found_channel_as_written() {
+    let file = legacy("1.72");
+    let requirement =
+        Requirement::new().with_channel(ChannelRequirement::AtLeast("1.73.0".to_string()));
+
+    assert_eq!(
+        file.satisfies(&requirement),
+        vec![Unmet::Channel {
+            required: ChannelRequirement::AtLeast("1.73.0".to_string()),
+            found: Some("1.72".to_string()),
+        }]
+    );
+}
+
+#[parameterized(
+    minimal_without_profile = { None, "rustc", true },
+    clippy_without_profile = { None, "clippy", false },
+    clippy_in_minimal = { Some("minimal"), "clippy-preview", false },
+    clippy_in_default = { Some("default"), "clippy-preview", true },
+    rust_src_in_default = { Some("default"), "rust-src", false },
+    rust_src_in_complete = { Some("complete"), "rust-src", true },
+)]
+fn components_of_profile(profile: Option<&str>, component: &str, met: bool) {
+    let mut builder = RustToolchainToml::builder().with_channel("stable");
+
+    if let Some(profile) = profile {
+        builder = builder.with_profile(profile);
+    }
+
+    let requirement = Requirement::new().with_component(component);
+
+    assert_eq!(toml(builder).satisfies(&requirement).is_empty(), met);
+}
+
+#[test]
+fn local_toolchain() {
+    let file = legacy("/opt/rust");
+
+    assert!(file.satisfies(&Requirement::new()).is_empty());
+    assert_eq!(
+        file.satisfies(&Requirement::new().with_component("rust-src")),
+        vec![Unmet::LocalToolchain(PortablePath::from("/opt/rust"))]
+    );
+}
+
+#[parameterized(
+    channel = {
+        Unmet::Channel { required: nightly_since("2023-06-01"), found: Some("stable".to_string()) },
+        "requires a nightly toolchain released on or after 2023-06-01, but the channel is 'stable'"
+    },
+    no_channel = {
+        Unmet::Channel { required: ChannelRequirement::AtLeast("1.70.0".to_string()), found: None },
+        "requires a toolchain of version 1.70.0 or newer, but no channel is specified"
+    },
+    component = {
+        Unmet::Component(Component("rust-src".to_string())),
+        "add 'rust-src' to the components of the toolchain file"
+    },
+    target = {
+        Unmet::Target(Target("wasm32-unknown-unknown".to_string())),
+        "add 'wasm32-unknown-unknown' to the targets of the toolchain file"
+    },
+)]
+fn message(unmet: Unmet, expected: &str) {
+    assert_eq!(unmet.to_string(), expected);
+}
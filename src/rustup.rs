@@ -0,0 +1,177 @@
+//! The rustup releases which introduced the features of toolchain files.
+
+use crate::legacy::LegacyChannel;
+use crate::toml::ToolchainSection;
+use crate::{FileName, LegacyToolchainFile, RustToolchainToml, ToolchainFile};
+use alloc::vec::Vec;
+use core::fmt;
+
+#[cfg(all(test, any(feature = "use_toml_edit", feature = "use_toml")))]
+mod tests;
+
+/// A rustup release.
+#[derive(
+    Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, serde::Serialize, serde::Deserialize,
+)]
+pub struct RustupVersion {
+    major: u32,
+    minor: u32,
+    patch: u32,
+}
+
+impl RustupVersion {
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    pub fn major(&self) -> u32 {
+        self.major
+    }
+
+    pub fn minor(&self) -> u32 {
+        self.minor
+    }
+
+    pub fn patch(&self) -> u32 {
+        self.patch
+    }
+}
+
+impl fmt::Display for RustupVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// A feature of toolchain files, which is only understood by rustup since a specific release.
+#[derive(
+    Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, serde::Serialize, serde::Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum Feature {
+    /// A legacy toolchain file, which consists of a single toolchain name.
+    LegacyFile,
+    /// A toolchain file in the TOML format, with a `[toolchain]` table.
+    TomlFormat,
+    /// A toolchain file named `rust-toolchain.toml`.
+    TomlFileName,
+    /// A path to a custom toolchain, instead of a toolchain name, in a legacy toolchain file.
+    LegacyPath,
+    /// The `path` key in the `[toolchain]` table.
+    ToolchainPath,
+    /// A relative `path`, which is resolved against the directory of the toolchain file.
+    RelativeToolchainPath,
+    /// The `profile` key in the `[toolchain]` table.
+    Profile,
+}
+
+impl Feature {
+    /// The first rustup release which supports this feature.
+    ///
+    /// The releases are taken from the rustup CHANGELOG, of which the relevant entry is named for
+    /// each feature.
+    pub fn since(&self) -> RustupVersion {
+        match self {
+            // 1.5.0: overriding the toolchain with a `rust-toolchain` file.
+            Self::LegacyFile => RustupVersion::new(1, 5, 0),
+            // 1.22.0: the TOML format for toolchain files, with the `channel`, `components`,
+            // `targets` and `profile` keys. Profiles themselves were added in 1.20.0.
+            Self::TomlFormat | Self::Profile => RustupVersion::new(1, 22, 0),
+            // 1.23.0: the `rust-toolchain.toml` file name, and toolchain files which name a
+            // custom toolchain by its path, with the `path` key or as the legacy content.
+            Self::TomlFileName | Self::LegacyPath | Self::ToolchainPath => {
+                RustupVersion::new(1, 23, 0)
+            }
+            // 1.25.0: relative toolchain paths, resolved against the directory of the file.
+            Self::RelativeToolchainPath => RustupVersion::new(1, 25, 0),
+        }
+    }
+}
+
+impl fmt::Display for Feature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::LegacyFile => "a legacy toolchain file",
+            Self::TomlFormat => "the TOML toolchain file format",
+            Self::TomlFileName => "the 'rust-toolchain.toml' file name",
+            Self::LegacyPath => "a toolchain path in a legacy toolchain file",
+            Self::ToolchainPath => "the 'path' key",
+            Self::RelativeToolchainPath => "a relative 'path'",
+            Self::Profile => "the 'profile' key",
+        })
+    }
+}
+
+/// The minimum rustup release which supports all the given features.
+pub fn min_version(features: &[Feature]) -> RustupVersion {
+    features
+        .iter()
+        .map(Feature::since)
+        .max()
+        .unwrap_or(Feature::LegacyFile.since())
+}
+
+impl LegacyToolchainFile {
+    /// The features of toolchain files used by this file.
+    pub fn rustup_features(&self) -> Vec<Feature> {
+        match self.channel() {
+            LegacyChannel::Path(_) => alloc::vec![Feature::LegacyFile, Feature::LegacyPath],
+            LegacyChannel::Spec(_) => alloc::vec![Feature::LegacyFile],
+        }
+    }
+}
+
+impl RustToolchainToml {
+    /// The features of toolchain files used by this file.
+    pub fn rustup_features(&self) -> Vec<Feature> {
+        let mut features = alloc::vec![Feature::TomlFormat];
+
+        match self.toolchain() {
+            ToolchainSection::Path(path) => {
+                features.push(Feature::ToolchainPath);
+
+                if path.portable_path().is_relative() {
+                    features.push(Feature::RelativeToolchainPath);
+                }
+            }
+            ToolchainSection::Spec(spec) => {
+                if spec.profile().is_some() {
+                    features.push(Feature::Profile);
+                }
+            }
+        }
+
+        features
+    }
+}
+
+impl ToolchainFile {
+    /// The features of toolchain files used by this file.
+    ///
+    /// The file name is not part of the model; see [`FileName::rustup_features`].
+    pub fn rustup_features(&self) -> Vec<Feature> {
+        match self {
+            Self::Legacy(file) => file.rustup_features(),
+            Self::Toml(file) => file.rustup_features(),
+        }
+    }
+
+    /// The minimum rustup release which honors every feature used by this file.
+    pub fn min_rustup_version(&self) -> RustupVersion {
+        min_version(&self.rustup_features())
+    }
+}
+
+impl FileName {
+    /// The features of toolchain files required by a file with this name.
+    pub fn rustup_features(&self) -> Vec<Feature> {
+        match self {
+            Self::RustToolchain => Vec::new(),
+            Self::RustToolchainToml => alloc::vec![Feature::TomlFileName],
+        }
+    }
+}
@@ -0,0 +1,117 @@
+use crate::rustup::{Feature, RustupVersion};
+use crate::{
+    legacy, toml, FileName, ParseStrategy, Parser, ParserError, ParserOptions, ToolchainFile,
+    Warning,
+};
+use yare::parameterized;
+
+fn parse(content: &str) -> ToolchainFile {
+    Parser::new(content, ParseStrategy::Auto(FileName::RustToolchain))
+        .parse()
+        .unwrap()
+}
+
+#[test]
+fn version_order_and_display() {
+    assert!(RustupVersion::new(1, 9, 0) < RustupVersion::new(1, 23, 0));
+    assert!(RustupVersion::new(1, 23, 0) < RustupVersion::new(1, 23, 1));
+    assert_eq!(RustupVersion::new(1, 25, 2).to_string(), "1.25.2");
+}
+
+#[parameterized(
+    legacy_spec = { "stable", &[Feature::LegacyFile], RustupVersion::new(1, 5, 0) },
+    legacy_path = { "/opt/rust", &[Feature::LegacyFile, Feature::LegacyPath], RustupVersion::new(1, 23, 0) },
+    toml_channel = { "[toolchain]\nchannel = \"stable\"", &[Feature::TomlFormat], RustupVersion::new(1, 22, 0) },
+    toml_profile = { "[toolchain]\nprofile = \"minimal\"", &[Feature::TomlFormat, Feature::Profile], RustupVersion::new(1, 22, 0) },
+    toml_path = { "[toolchain]\npath = \"/opt/rust\"", &[Feature::TomlFormat, Feature::ToolchainPath], RustupVersion::new(1, 23, 0) },
+    toml_relative_path = { "[toolchain]\npath = \"rust\"", &[Feature::TomlFormat, Feature::ToolchainPath, Feature::RelativeToolchainPath], RustupVersion::new(1, 25, 0) },
+)]
+fn features(content: &str, expected: &[Feature], version: RustupVersion) {
+    let file = parse(content);
+
+    assert_eq!(file.rustup_features(), expected);
+    assert_eq!(file.min_rustup_version(), version);
+}
+
+#[test]
+fn outcome_includes_file_name() {
+    let outcome =
+        ToolchainFile::from_path("tests/fixtures/rustup-book-layout/rust-toolchain.toml").unwrap();
+
+    assert!(outcome.rustup_features().contains(&Feature::TomlFileName));
+    assert_eq!(outcome.min_rustup_version(), RustupVersion::new(1, 23, 0));
+}
+
+mod compat {
+    use super::*;
+
+    #[test]
+    fn accepts_supported_features() {
+        let options = ParserOptions::default().with_rustup_version(RustupVersion::new(1, 22, 0));
+        let parser = toml::Parser::new("[toolchain]\nchannel = \"stable\"").with_options(options);
+
+        assert!(parser.parse().is_ok());
+    }
+
+    #[test]
+    fn toml_rejects_unsupported_feature() {
+        let version = RustupVersion::new(1, 22, 1);
+        let options = ParserOptions::default().with_rustup_version(version);
+        let parser = toml::Parser::new("[toolchain]\npath = \"/opt/rust\"").with_options(options);
+
+        assert_eq!(
+            parser.parse().unwrap_err(),
+            toml::ParserError::Denied(Warning::UnsupportedFeature {
+                feature: Feature::ToolchainPath,
+                version,
+            })
+        );
+    }
+
+    #[test]
+    fn legacy_rejects_unsupported_feature() {
+        let version = RustupVersion::new(1, 22, 1);
+        let options = ParserOptions::default().with_rustup_version(version);
+        let parser = legacy::Parser::new("/opt/rust").with_options(options);
+
+        assert_eq!(
+            parser.parse().unwrap_err(),
+            legacy::ParserError::Denied(Warning::UnsupportedFeature {
+                feature: Feature::LegacyPath,
+                version,
+            })
+        );
+    }
+
+    #[test]
+    fn auto_rejects_unsupported_file_name() {
+        let version = RustupVersion::new(1, 22, 1);
+        let options = ParserOptions::default().with_rustup_version(version);
+        let parser = Parser::new(
+            "[toolchain]\nchannel = \"stable\"",
+            ParseStrategy::Auto(FileName::RustToolchainToml),
+        )
+        .with_options(options);
+
+        assert_eq!(
+            parser.parse().unwrap_err(),
+            ParserError::Denied(Warning::UnsupportedFeature {
+                feature: Feature::TomlFileName,
+                version,
+            })
+        );
+    }
+
+    #[test]
+    fn message() {
+        let warning = Warning::UnsupportedFeature {
+            feature: Feature::Profile,
+            version: RustupVersion::new(1, 20, 0),
+        };
+
+        assert_eq!(
+            warning.to_string(),
+            "Found the 'profile' key, which requires rustup 1.22.0, but rustup 1.20.0 was selected"
+        );
+    }
+}
@@ -0,0 +1,106 @@
+//! The serialized representation of the model, for exchanging toolchain files and reports, for
+//! example as JSON.
+//!
+//! Every public type of the model implements `Serialize` and `Deserialize`, except for errors
+//! which hold an I/O error, which only implement `Serialize`. Parsers are not part of the model.
+//!
+//! # Version 1
+//!
+//! The representation follows a few rules:
+//!
+//! * An enum without data is a `snake_case` string, like `"legacy"` for [`Variant::Legacy`].
+//! * An enum with data is an object with a `snake_case` `kind`, and its data, if any, as `value`,
+//!   like `{"kind":"spec","value":"stable"}` for a [`LegacyChannel::Spec`].
+//! * A struct is an object with a field for each of its fields, where an absent value is `null`.
+//! * The `toolchain` table of a [`RustToolchainToml`] keeps the layout of the TOML file, so the
+//!   model can also be read from, and written to, TOML. Its `path`, or its `channel`, `components`,
+//!   `targets` and `profile`, tell a path and a specification apart.
+//! * The names of the toolchain, like the channel and components, and paths, are strings.
+//! * A [`Fingerprint`](crate::fingerprint::Fingerprint) is a string of 32 hexadecimal digits.
+//!
+//! For example, a TOML toolchain file is represented as:
+//!
+//! ```json
+//! {
+//!   "kind": "toml",
+//!   "value": {
+//!     "toolchain": {
+//!       "channel": "nightly-2020-07-10",
+//!       "components": ["rustfmt", "rustc-dev"],
+//!       "targets": null,
+//!       "profile": "minimal"
+//!     }
+//!   }
+//! }
+//! ```
+//!
+//! A legacy toolchain file is represented as
+//! `{"kind":"legacy","value":{"channel":{"kind":"path","value":"/opt/rust"}}}`.
+//!
+//! To exchange a value along with the version of its representation, wrap it in a [`Versioned`].
+//! The representation only changes with a new version.
+//!
+//! [`RustToolchainToml`]: crate::RustToolchainToml
+//! [`Variant::Legacy`]: crate::Variant::Legacy
+//! [`LegacyChannel::Spec`]: crate::legacy::LegacyChannel::Spec
+
+use serde::{de, Deserialize, Deserializer};
+
+#[cfg(test)]
+mod tests;
+
+/// The version of the representation described by this module.
+pub const VERSION: u32 = 1;
+
+/// A value, along with the version of its representation, like `{"version":1,"value":...}`.
+///
+/// Deserialization fails for any other version than [`VERSION`].
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Versioned<T> {
+    #[serde(deserialize_with = "deserialize_version")]
+    version: u32,
+    value: T,
+}
+
+impl<T> Versioned<T> {
+    pub fn new(value: T) -> Self {
+        Self {
+            version: VERSION,
+            value,
+        }
+    }
+
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+
+    pub fn into_value(self) -> T {
+        self.value
+    }
+}
+
+fn deserialize_version<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u32, D::Error> {
+    let version = u32::deserialize(deserializer)?;
+
+    if version != VERSION {
+        return Err(de::Error::invalid_value(
+            de::Unexpected::Unsigned(version.into()),
+            &"version 1",
+        ));
+    }
+
+    Ok(version)
+}
+
+/// Serialize an I/O error as its message.
+#[cfg(any(feature = "use_toml_edit", feature = "use_toml"))]
+pub(crate) fn serialize_io_error<S: serde::Serializer>(
+    error: &std::io::Error,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(error)
+}
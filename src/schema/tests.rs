@@ -0,0 +1,152 @@
+use crate::fingerprint::Fingerprint;
+use crate::schema::{Versioned, VERSION};
+use crate::test_helpers::{legacy, toml};
+use crate::toml::{BuildError, Key};
+use crate::{RustToolchainToml, ToolchainFile, Variant, Warning};
+use serde_json::json;
+use yare::parameterized;
+
+#[test]
+fn documented_toml_example() {
+    let file = toml(
+        RustToolchainToml::builder()
+            .with_channel("nightly-2020-07-10")
+            .with_components(["rustfmt", "rustc-dev"])
+            .with_profile("minimal"),
+    );
+
+    assert_eq!(
+        serde_json::to_value(&file).unwrap(),
+        json!({
+            "kind": "toml",
+            "value": {
+                "toolchain": {
+                    "channel": "nightly-2020-07-10",
+                    "components": ["rustfmt", "rustc-dev"],
+                    "targets": null,
+                    "profile": "minimal"
+                }
+            }
+        })
+    );
+}
+
+#[test]
+fn documented_legacy_example() {
+    assert_eq!(
+        serde_json::to_string(&legacy("/opt/rust")).unwrap(),
+        r#"{"kind":"legacy","value":{"channel":{"kind":"path","value":"/opt/rust"}}}"#
+    );
+}
+
+#[parameterized(
+    legacy_spec = { legacy("stable") },
+    legacy_path = { legacy("/opt/rust") },
+    toml_spec = { toml(RustToolchainToml::builder().with_channel("1.70.0").with_target("wasm32-unknown-unknown")) },
+    toml_path = { toml(RustToolchainToml::builder().with_path("/opt/rust")) },
+)]
+fn round_trip(file: ToolchainFile) {
+    let json = serde_json::to_string(&Versioned::new(file.clone())).unwrap();
+    let versioned: Versioned<ToolchainFile> = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(versioned.version(), VERSION);
+    assert_eq!(versioned.into_value(), file);
+}
+
+#[test]
+fn versioned() {
+    assert_eq!(
+        serde_json::to_string(&Versioned::new(Variant::Toml)).unwrap(),
+        r#"{"version":1,"value":"toml"}"#
+    );
+}
+
+#[parameterized(
+    newer = { r#"{"version":2,"value":"toml"}"# },
+    older = { r#"{"version":0,"value":"toml"}"# },
+)]
+fn reject_other_versions(json: &str) {
+    assert!(serde_json::from_str::<Versioned<Variant>>(json).is_err());
+}
+
+#[test]
+fn unit_variant() {
+    let json = json!({ "kind": "surrounding_whitespace" });
+
+    assert_eq!(
+        serde_json::to_value(Warning::SurroundingWhitespace).unwrap(),
+        json
+    );
+    assert_eq!(
+        serde_json::from_value::<Warning>(json).unwrap(),
+        Warning::SurroundingWhitespace
+    );
+}
+
+#[test]
+fn key() {
+    let error = BuildError::Empty(Key::Profile);
+    let json = json!({ "kind": "empty", "value": "profile" });
+
+    assert_eq!(serde_json::to_value(&error).unwrap(), json);
+    assert_eq!(serde_json::from_value::<BuildError>(json).unwrap(), error);
+}
+
+#[test]
+fn reject_unknown_key() {
+    let json = json!({ "kind": "empty", "value": "toolchain" });
+
+    assert!(serde_json::from_value::<BuildError>(json).is_err());
+}
+
+#[test]
+fn fingerprint() {
+    let fingerprint = legacy("stable").fingerprint();
+    let json = serde_json::to_string(&fingerprint).unwrap();
+
+    assert_eq!(json, r#""1edac8317d88ebc442f98b990492d42a""#);
+    assert_eq!(
+        serde_json::from_str::<Fingerprint>(&json).unwrap(),
+        fingerprint
+    );
+}
+
+#[parameterized(
+    too_short = { r#""1edac831""# },
+    uppercase = { r#""1EDAC8317D88EBC442F98B990492D42A""# },
+    not_hex = { r#""zedac8317d88ebc442f98b990492d42a""# },
+)]
+fn reject_invalid_fingerprint(json: &str) {
+    assert!(serde_json::from_str::<Fingerprint>(json).is_err());
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn diff_matches_to_json() {
+    let from = legacy("1.70");
+    let to = toml(
+        RustToolchainToml::builder()
+            .with_channel("1.72.1")
+            .with_component("clippy")
+            .with_profile("minimal"),
+    );
+    let diff = from.diff(&to);
+
+    assert_eq!(serde_json::to_string(&diff).unwrap(), diff.to_json());
+}
+
+#[cfg(any(feature = "use_toml_edit", feature = "use_toml"))]
+#[test]
+fn too_many_items() {
+    let error = crate::toml::ParserError::TooManyItems {
+        key: Key::Components,
+        limit: 2,
+    };
+    let json = json!({ "kind": "too_many_items", "value": { "key": "components", "limit": 2 } });
+
+    assert_eq!(serde_json::to_value(&error).unwrap(), json);
+    assert_eq!(
+        serde_json::from_value::<crate::toml::ParserError>(json).unwrap(),
+        error
+    );
+}
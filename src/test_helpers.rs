@@ -0,0 +1,16 @@
+//! Shorthands to construct toolchain files in tests.
+
+use crate::toml::Builder;
+use crate::{legacy, RustToolchainToml, ToolchainFile};
+
+pub(crate) fn build(builder: Builder) -> RustToolchainToml {
+    builder.build().unwrap()
+}
+
+pub(crate) fn toml(builder: Builder) -> ToolchainFile {
+    ToolchainFile::Toml(build(builder))
+}
+
+pub(crate) fn legacy(content: &str) -> ToolchainFile {
+    ToolchainFile::Legacy(legacy::Parser::new(content).parse().unwrap())
+}
@@ -33,7 +33,7 @@ mod parser_new {
         let content = "hello-world";
         let parser = Parser::new(content, option);
 
-        assert_eq!(parser.content, content);
+        assert_eq!(parser.content, content.as_bytes());
         assert_eq!(parser.parse_option, option);
     }
 }
@@ -167,3 +167,274 @@ mod fallback {
         }
     }
 }
+
+mod options {
+    use crate::tests::LEGACY_ONLY;
+    use crate::{legacy, ParseStrategy, Parser, ParserError, ParserOptions, Variant, Warning};
+
+    #[test]
+    fn default_options() {
+        let parser = Parser::new(LEGACY_ONLY, ParseStrategy::Only(Variant::Legacy));
+
+        assert_eq!(parser.options, ParserOptions::default());
+    }
+
+    #[test]
+    fn options_are_passed_on() {
+        let strategy = ParseStrategy::Fallback {
+            first: Variant::Legacy,
+            fallback_to: Variant::Toml,
+        };
+
+        let parser = Parser::new(" nightly ", strategy).with_options(ParserOptions::pedantic());
+
+        let result = parser.parse();
+        let ParserError::FallbackError(error) = result.unwrap_err() else {
+            panic!("expected a fallback error");
+        };
+
+        assert_eq!(
+            error.first(),
+            &ParserError::LegacyParseError(legacy::ParserError::Denied(
+                Warning::SurroundingWhitespace
+            ))
+        );
+    }
+}
+
+mod auto {
+    use crate::legacy::LegacyChannel;
+    use crate::tests::{LEGACY_ONLY, TOML_LOCAL_PATH, TOML_WITHOUT_EXT, TOML_WITH_EXT};
+    use crate::{
+        FileName, Hint, LegacyToolchainFile, ParseStrategy, Parser, ParserError, ToolchainFile,
+        Variant,
+    };
+    use yare::parameterized;
+
+    #[parameterized(
+        rust_toolchain = { "rust-toolchain", Some(FileName::RustToolchain) },
+        rust_toolchain_toml = { "rust-toolchain.toml", Some(FileName::RustToolchainToml) },
+        in_directory = { "project/rust-toolchain.toml", Some(FileName::RustToolchainToml) },
+        other_extension = { "rust-toolchain.txt", None },
+        other_name = { "Cargo.toml", None },
+        directory_only = { "project/", None },
+    )]
+    fn file_name_from_path(path: &str, expected: Option<FileName>) {
+        assert_eq!(FileName::from_path(path), expected);
+        assert_eq!(
+            ParseStrategy::from_file_name(path),
+            expected.map(ParseStrategy::Auto)
+        );
+    }
+
+    #[test]
+    fn file_name_as_str() {
+        assert_eq!(FileName::RustToolchain.as_str(), "rust-toolchain");
+        assert_eq!(FileName::RustToolchainToml.as_str(), "rust-toolchain.toml");
+    }
+
+    #[parameterized(
+        legacy = { LEGACY_ONLY, Some(Variant::Legacy) },
+        toml = { TOML_WITH_EXT, Some(Variant::Toml) },
+        toml_key_value = { "channel = \"stable\"", Some(Variant::Toml) },
+        empty = { "  \n", None },
+        multiple_lines = { "stable\nnightly", None },
+        path_with_equals_sign = { "/opt/rust=1.70", Some(Variant::Legacy) },
+        windows_path_with_equals_sign = { r"C:\rust=1.70", Some(Variant::Legacy) },
+    )]
+    fn sniff(content: &str, expected: Option<Variant>) {
+        assert_eq!(Variant::sniff(content), expected);
+    }
+
+    #[parameterized(
+        toml_with_ext = { TOML_WITH_EXT },
+        toml_local_path = { TOML_LOCAL_PATH },
+    )]
+    fn rust_toolchain_toml_accepts_toml(content: &str) {
+        let parser = Parser::new(content, ParseStrategy::Auto(FileName::RustToolchainToml));
+
+        let result = parser.parse();
+        assert!(matches!(result.unwrap(), ToolchainFile::Toml(_)));
+    }
+
+    #[test]
+    fn rust_toolchain_toml_rejects_legacy_with_hint() {
+        let parser = Parser::new(
+            LEGACY_ONLY,
+            ParseStrategy::Auto(FileName::RustToolchainToml),
+        );
+
+        let result = parser.parse();
+        let ParserError::HintedError(error) = result.unwrap_err() else {
+            panic!("expected a hinted error");
+        };
+
+        assert_eq!(error.hint(), Hint::LegacyContentInTomlFile);
+        assert!(matches!(error.error(), ParserError::TomlParseError(_)));
+    }
+
+    #[test]
+    fn rust_toolchain_accepts_toml() {
+        let parser = Parser::new(
+            TOML_WITHOUT_EXT,
+            ParseStrategy::Auto(FileName::RustToolchain),
+        );
+
+        let result = parser.parse();
+        assert!(matches!(result.unwrap(), ToolchainFile::Toml(_)));
+    }
+
+    #[test]
+    fn rust_toolchain_accepts_legacy() {
+        let parser = Parser::new(LEGACY_ONLY, ParseStrategy::Auto(FileName::RustToolchain));
+
+        let result = parser.parse();
+        assert_eq!(
+            result.unwrap(),
+            ToolchainFile::Legacy(LegacyToolchainFile::new(LegacyChannel::Spec(
+                "nightly-2020-07-10".to_string()
+            )))
+        );
+    }
+
+    #[test]
+    fn rust_toolchain_rejects_broken_toml_with_hint() {
+        let content = "[toolchain]\nchannel = nightly\n";
+        let parser = Parser::new(content, ParseStrategy::Auto(FileName::RustToolchain));
+
+        let result = parser.parse();
+        let ParserError::HintedError(error) = result.unwrap_err() else {
+            panic!("expected a hinted error");
+        };
+
+        assert_eq!(error.hint(), Hint::InvalidToml);
+        assert!(matches!(error.error(), ParserError::FallbackError(_)));
+    }
+
+    #[test]
+    fn rust_toolchain_rejects_empty_without_hint() {
+        let parser = Parser::new("", ParseStrategy::Auto(FileName::RustToolchain));
+
+        let result = parser.parse();
+        assert!(matches!(result.unwrap_err(), ParserError::FallbackError(_)));
+    }
+}
+
+mod outcome {
+    use crate::tests::{LEGACY_ONLY, TOML_WITHOUT_EXT};
+    use crate::{
+        toml, FileName, ParseStrategy, Parser, ParserError, ParserOptions, ToolchainFile, Variant,
+        Warning,
+    };
+
+    #[test]
+    fn only() {
+        let parser = Parser::new(LEGACY_ONLY, ParseStrategy::Only(Variant::Legacy));
+
+        let outcome = parser.parse_outcome().unwrap();
+        assert_eq!(outcome.variant(), Variant::Legacy);
+        assert_eq!(outcome.attempts(), &[Variant::Legacy]);
+        assert!(outcome.discarded().is_empty());
+        assert!(outcome.warnings().is_empty());
+        assert!(!outcome.is_fallback());
+        assert!(matches!(outcome.file(), ToolchainFile::Legacy(_)));
+    }
+
+    #[test]
+    fn first_attempt_succeeds() {
+        let strategy = ParseStrategy::Fallback {
+            first: Variant::Toml,
+            fallback_to: Variant::Legacy,
+        };
+        let parser = Parser::new(TOML_WITHOUT_EXT, strategy);
+
+        let outcome = parser.parse_outcome().unwrap();
+        assert_eq!(outcome.variant(), Variant::Toml);
+        assert_eq!(outcome.attempts(), &[Variant::Toml]);
+        assert!(!outcome.is_fallback());
+    }
+
+    #[test]
+    fn fallback_keeps_discarded_error() {
+        let strategy = ParseStrategy::Fallback {
+            first: Variant::Toml,
+            fallback_to: Variant::Legacy,
+        };
+        let parser = Parser::new(LEGACY_ONLY, strategy);
+
+        let outcome = parser.parse_outcome().unwrap();
+        assert_eq!(outcome.variant(), Variant::Legacy);
+        assert_eq!(outcome.attempts(), &[Variant::Toml, Variant::Legacy]);
+        assert!(outcome.is_fallback());
+        assert!(matches!(
+            outcome.discarded(),
+            [ParserError::TomlParseError(toml::ParserError::TomlParse(_))]
+        ));
+        assert!(outcome.warnings().is_empty());
+    }
+
+    #[test]
+    fn broken_toml_read_as_legacy_warns() {
+        let content = "toolchain = { channel = stable }";
+        let parser = Parser::new(content, ParseStrategy::Auto(FileName::RustToolchain));
+
+        let outcome = parser.parse_outcome().unwrap();
+        assert_eq!(outcome.variant(), Variant::Legacy);
+        assert_eq!(outcome.warnings(), &[Warning::TomlReadAsLegacy]);
+    }
+
+    #[test]
+    fn broken_toml_read_as_legacy_promoted() {
+        let content = "toolchain = { channel = stable }";
+        let strategy = ParseStrategy::Fallback {
+            first: Variant::Toml,
+            fallback_to: Variant::Legacy,
+        };
+        let parser = Parser::new(content, strategy)
+            .with_options(ParserOptions::rustup_exact().with_warnings_as_errors(true));
+
+        let result = parser.parse_outcome();
+        assert_eq!(
+            result.unwrap_err(),
+            ParserError::Denied(Warning::TomlReadAsLegacy)
+        );
+    }
+
+    #[test]
+    fn warnings_of_successful_attempt() {
+        let options = ParserOptions::pedantic().with_warnings_as_errors(false);
+        let parser =
+            Parser::new(" nightly ", ParseStrategy::Only(Variant::Legacy)).with_options(options);
+
+        let outcome = parser.parse_outcome().unwrap();
+        assert_eq!(outcome.warnings(), &[Warning::SurroundingWhitespace]);
+    }
+
+    #[test]
+    fn parse_is_outcome_file() {
+        let parser = Parser::new(LEGACY_ONLY, ParseStrategy::Auto(FileName::RustToolchain));
+
+        assert_eq!(
+            parser.parse().unwrap(),
+            parser.parse_outcome().unwrap().into_file()
+        );
+    }
+}
+
+mod from_slice {
+    use crate::tests::TOML_WITH_EXT;
+    use crate::{ParseStrategy, Parser, ToolchainFile, Variant};
+
+    #[test]
+    fn new_and_from_slice_are_alike() {
+        let strategy = ParseStrategy::Only(Variant::Toml);
+
+        let from_str = Parser::new(TOML_WITH_EXT, strategy).parse().unwrap();
+        let from_slice = Parser::from_slice(TOML_WITH_EXT.as_bytes(), strategy)
+            .parse()
+            .unwrap();
+
+        assert_eq!(from_str, from_slice);
+        assert!(matches!(from_slice, ToolchainFile::Toml(_)));
+    }
+}
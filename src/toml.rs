@@ -1,40 +1,41 @@
-#[cfg(test)]
+#[cfg(all(test, any(feature = "use_toml_edit", feature = "use_toml")))]
+#[allow(clippy::redundant_static_lifetimes)]
 mod tests;
 
-use camino::{Utf8Path, Utf8PathBuf};
-
-/// A parser for the TOML based toolchain file format.
-pub struct Parser<'content> {
-    content: &'content [u8],
-}
-
-impl<'content> Parser<'content> {
-    /// Initialize a parser for the `&str` content.
-    pub fn new(content: &'content str) -> Self {
-        Self {
-            content: content.as_bytes(),
-        }
-    }
-
-    /// Initialize a parser.
-    pub fn from_slice(content: &'content [u8]) -> Self {
-        Self { content }
-    }
-}
-
-impl Parser<'_> {
-    pub fn parse(&self) -> Result<RustToolchainToml, ParserError> {
-        toml_edit::de::from_slice(self.content).map_err(ParserError::TomlParse)
-    }
-}
-
-#[derive(Debug, thiserror::Error, PartialEq)]
-pub enum ParserError {
-    #[error("Unable to parse toolchain file: {0}")]
-    TomlParse(toml_edit::de::Error),
-}
-
-#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[cfg(any(feature = "use_toml_edit", feature = "use_toml"))]
+mod backend;
+mod builder;
+mod convert;
+#[cfg(feature = "use_toml_edit")]
+pub mod edit;
+#[cfg(feature = "use_toml_edit")]
+pub mod format;
+mod merge;
+pub(crate) mod normalize;
+#[cfg(any(feature = "use_toml_edit", feature = "use_toml"))]
+mod parser;
+#[cfg(any(feature = "use_toml_edit", feature = "use_toml"))]
+mod recover;
+pub(crate) mod release;
+mod serialize;
+
+pub use builder::{BuildError, Builder};
+pub use convert::ConvertError;
+pub use merge::{ChannelPolicy, MergeError};
+
+#[cfg(any(feature = "use_toml_edit", feature = "use_toml"))]
+pub use {
+    parser::{Parser, ParserError, TomlError},
+    recover::{Diagnostic, Recovered, Severity},
+};
+
+#[cfg(feature = "std")]
+use crate::path::{self, ResolveError, ResolvedPath};
+use crate::PortablePath;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct RustToolchainToml {
     toolchain: ToolchainSection,
 }
@@ -45,7 +46,7 @@ impl RustToolchainToml {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 #[serde(untagged)]
 pub enum ToolchainSection {
     Path(ToolchainPath),
@@ -68,7 +69,7 @@ impl ToolchainSection {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct ToolchainSpec {
     channel: Option<Channel>,
     components: Option<Vec<Component>>,
@@ -77,6 +78,16 @@ pub struct ToolchainSpec {
 }
 
 impl ToolchainSpec {
+    /// A specification without a channel, components, targets or profile.
+    pub(crate) fn empty() -> Self {
+        Self {
+            channel: None,
+            components: None,
+            targets: None,
+            profile: None,
+        }
+    }
+
     pub fn channel(&self) -> Option<&Channel> {
         self.channel.as_ref()
     }
@@ -94,18 +105,49 @@ impl ToolchainSpec {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct ToolchainPath {
-    path: Utf8PathBuf,
+    path: PortablePath,
 }
 
 impl ToolchainPath {
-    pub fn path(&self) -> &Utf8Path {
+    #[cfg(feature = "std")]
+    pub fn path(&self) -> &camino::Utf8Path {
+        self.path.as_utf8_path()
+    }
+
+    pub fn portable_path(&self) -> &PortablePath {
         &self.path
     }
+
+    /// Resolve the path against the directory which holds the toolchain file at the given location.
+    ///
+    /// A relative path is joined to the directory of the toolchain file, and `..` segments are
+    /// normalized, so the result is absolute. The location of the toolchain file must be absolute. A
+    /// warning is included when the path leaves the directory of the toolchain file.
+    #[cfg(feature = "std")]
+    pub fn resolve(
+        &self,
+        toolchain_file: impl AsRef<camino::Utf8Path>,
+    ) -> Result<ResolvedPath, ResolveError> {
+        path::resolve(&self.path, toolchain_file.as_ref(), None)
+    }
+
+    /// Like [`ToolchainPath::resolve`], but warn when the path leaves the given repository root
+    /// instead, which must be absolute as well.
+    #[cfg(feature = "std")]
+    pub fn resolve_within(
+        &self,
+        toolchain_file: impl AsRef<camino::Utf8Path>,
+        root: impl AsRef<camino::Utf8Path>,
+    ) -> Result<ResolvedPath, ResolveError> {
+        path::resolve(&self.path, toolchain_file.as_ref(), Some(root.as_ref()))
+    }
 }
 
-#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[derive(
+    Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, serde::Serialize, serde::Deserialize,
+)]
 pub struct Channel(String);
 
 impl Channel {
@@ -114,8 +156,10 @@ impl Channel {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
-pub struct Component(String);
+#[derive(
+    Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, serde::Serialize, serde::Deserialize,
+)]
+pub struct Component(pub(crate) String);
 
 impl Component {
     pub fn name(&self) -> &str {
@@ -123,8 +167,10 @@ impl Component {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
-pub struct Target(String);
+#[derive(
+    Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, serde::Serialize, serde::Deserialize,
+)]
+pub struct Target(pub(crate) String);
 
 impl Target {
     pub fn name(&self) -> &str {
@@ -132,7 +178,9 @@ impl Target {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[derive(
+    Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, serde::Serialize, serde::Deserialize,
+)]
 pub struct Profile(String);
 
 impl Profile {
@@ -140,3 +188,32 @@ impl Profile {
         &self.0
     }
 }
+
+/// A key of the `[toolchain]` table.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Key {
+    Path,
+    Channel,
+    Components,
+    Targets,
+    Profile,
+}
+
+impl Key {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Path => "path",
+            Self::Channel => "channel",
+            Self::Components => "components",
+            Self::Targets => "targets",
+            Self::Profile => "profile",
+        }
+    }
+}
+
+impl core::fmt::Display for Key {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
@@ -0,0 +1,49 @@
+//! The TOML library used for deserialization, as selected by the `use_toml_edit` and `use_toml`
+//! cargo features. When both are enabled, `toml_edit` is used.
+
+use crate::toml::TomlError;
+use serde::Deserialize;
+
+/// Deserialize the TOML content.
+pub(crate) fn from_str<'de, T: Deserialize<'de>>(content: &'de str) -> Result<T, TomlError> {
+    #[cfg(feature = "use_toml_edit")]
+    {
+        let deserializer = content.parse::<toml_edit::de::Deserializer>()?;
+        Ok(T::deserialize(deserializer)?)
+    }
+
+    #[cfg(all(feature = "use_toml", not(feature = "use_toml_edit")))]
+    {
+        Ok(T::deserialize(toml_rs::Deserializer::new(content))?)
+    }
+}
+
+/// Deserialize the TOML content, after verifying it is valid UTF-8.
+pub(crate) fn from_slice<'de, T: Deserialize<'de>>(content: &'de [u8]) -> Result<T, TomlError> {
+    let content = std::str::from_utf8(content).map_err(|err| TomlError {
+        message: err.to_string(),
+        span: None,
+    })?;
+
+    from_str(content)
+}
+
+#[cfg(feature = "use_toml_edit")]
+impl From<toml_edit::de::Error> for TomlError {
+    fn from(err: toml_edit::de::Error) -> Self {
+        Self {
+            message: err.to_string(),
+            span: err.span(),
+        }
+    }
+}
+
+#[cfg(feature = "use_toml")]
+impl From<toml_rs::de::Error> for TomlError {
+    fn from(err: toml_rs::de::Error) -> Self {
+        Self {
+            message: err.to_string(),
+            span: err.span(),
+        }
+    }
+}
@@ -0,0 +1,175 @@
+use crate::legacy::{self, GrammarViolation};
+use crate::toml::{
+    Channel, Component, Key, Profile, RustToolchainToml, Target, ToolchainPath, ToolchainSection,
+    ToolchainSpec,
+};
+use crate::PortablePath;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+#[cfg(all(test, any(feature = "use_toml_edit", feature = "use_toml")))]
+mod tests;
+
+impl RustToolchainToml {
+    /// Construct a toolchain file, without formatting and parsing TOML.
+    pub fn builder() -> Builder {
+        Builder::default()
+    }
+}
+
+/// A builder for a [`RustToolchainToml`], which is validated by [`Builder::build`].
+///
+/// A toolchain file either specifies a toolchain `path`, or a `channel`, optionally with
+/// `components`, `targets` and a `profile`.
+#[derive(Clone, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Builder {
+    path: Option<PortablePath>,
+    channel: Option<String>,
+    components: Option<Vec<String>>,
+    targets: Option<Vec<String>>,
+    profile: Option<String>,
+}
+
+impl Builder {
+    pub fn with_channel(mut self, channel: impl Into<String>) -> Self {
+        self.channel = Some(channel.into());
+        self
+    }
+
+    pub fn with_path(mut self, path: impl Into<PortablePath>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    pub fn with_component(mut self, component: impl Into<String>) -> Self {
+        self.components
+            .get_or_insert_with(Vec::new)
+            .push(component.into());
+        self
+    }
+
+    /// Add the given components. An empty iterator results in an empty `components` array.
+    pub fn with_components<I: IntoIterator<Item = S>, S: Into<String>>(
+        mut self,
+        components: I,
+    ) -> Self {
+        self.components
+            .get_or_insert_with(Vec::new)
+            .extend(components.into_iter().map(Into::into));
+        self
+    }
+
+    pub fn with_target(mut self, target: impl Into<String>) -> Self {
+        self.targets
+            .get_or_insert_with(Vec::new)
+            .push(target.into());
+        self
+    }
+
+    /// Add the given targets. An empty iterator results in an empty `targets` array.
+    pub fn with_targets<I: IntoIterator<Item = S>, S: Into<String>>(mut self, targets: I) -> Self {
+        self.targets
+            .get_or_insert_with(Vec::new)
+            .extend(targets.into_iter().map(Into::into));
+        self
+    }
+
+    pub fn with_profile(mut self, profile: impl Into<String>) -> Self {
+        self.profile = Some(profile.into());
+        self
+    }
+
+    /// Validate the toolchain file, and construct it.
+    pub fn build(self) -> Result<RustToolchainToml, BuildError> {
+        let has_spec_keys = self.channel.is_some()
+            || self.components.is_some()
+            || self.targets.is_some()
+            || self.profile.is_some();
+
+        let toolchain = match self.path {
+            Some(_) if has_spec_keys => return Err(BuildError::PathWithSpecKeys),
+            Some(path) if path.as_str().is_empty() => return Err(BuildError::Empty(Key::Path)),
+            Some(path) => ToolchainSection::Path(ToolchainPath { path }),
+            None => {
+                let channel = self.channel.ok_or(BuildError::MissingChannelOrPath)?;
+                validate_channel(&channel)?;
+
+                let non_empty = |key, value: &String| match value.trim().is_empty() {
+                    true => Err(BuildError::Empty(key)),
+                    false => Ok(()),
+                };
+
+                for component in self.components.iter().flatten() {
+                    non_empty(Key::Components, component)?;
+                }
+
+                for target in self.targets.iter().flatten() {
+                    non_empty(Key::Targets, target)?;
+                }
+
+                if let Some(ref profile) = self.profile {
+                    non_empty(Key::Profile, profile)?;
+                }
+
+                ToolchainSection::Spec(ToolchainSpec {
+                    channel: Some(Channel(channel)),
+                    components: self
+                        .components
+                        .map(|c| c.into_iter().map(Component).collect()),
+                    targets: self.targets.map(|t| t.into_iter().map(Target).collect()),
+                    profile: self.profile.map(Profile),
+                })
+            }
+        };
+
+        Ok(RustToolchainToml { toolchain })
+    }
+}
+
+fn validate_channel(channel: &str) -> Result<(), BuildError> {
+    if channel.is_empty() {
+        return Err(BuildError::Empty(Key::Channel));
+    }
+
+    if PortablePath::from(channel).is_absolute() {
+        return Err(BuildError::ChannelIsPath);
+    }
+
+    legacy::validate(channel).map_err(BuildError::InvalidChannel)
+}
+
+/// A reason why a [`Builder`] does not describe a valid toolchain file.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "kind", content = "value", rename_all = "snake_case")]
+pub enum BuildError {
+    /// Neither a channel, nor a path, was given.
+    MissingChannelOrPath,
+    /// A path was combined with a channel, components, targets or a profile.
+    PathWithSpecKeys,
+    /// A path was given as the channel; use [`Builder::with_path`] instead.
+    ChannelIsPath,
+    /// The channel is not a valid toolchain name.
+    InvalidChannel(GrammarViolation),
+    /// The value of the given key is empty.
+    Empty(Key),
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingChannelOrPath => {
+                f.write_str("Unable to build toolchain file: expected a channel or a path")
+            }
+            Self::PathWithSpecKeys => f.write_str("Unable to build toolchain file: a path can not be combined with a channel, components, targets or profile"),
+            Self::ChannelIsPath => f.write_str("Unable to build toolchain file: the channel is a path, which must be given as the path instead"),
+            Self::InvalidChannel(violation) => {
+                write!(f, "Unable to build toolchain file: invalid channel, {violation}")
+            }
+            Self::Empty(key) => write!(f, "Unable to build toolchain file: '{key}' is empty"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for BuildError {}
@@ -0,0 +1,82 @@
+use crate::legacy::GrammarViolation;
+use crate::toml::{BuildError, Builder, Key, Parser};
+use crate::RustToolchainToml;
+use yare::parameterized;
+
+const RUSTUP_BOOK_SPEC: &str =
+    include_str!("../../../tests/fixtures/rustup-book-layout/rust-toolchain.toml");
+
+const RUSTUP_BOOK_LOCAL_TOOLCHAIN: &str =
+    include_str!("../../../tests/fixtures/rustup-book-local-toolchain/rust-toolchain.toml");
+
+#[test]
+fn spec_equals_parsed() {
+    let built = RustToolchainToml::builder()
+        .with_channel("nightly-2020-07-10")
+        .with_components(["rustfmt", "rustc-dev"])
+        .with_target("wasm32-unknown-unknown")
+        .with_target("thumbv2-none-eabi")
+        .with_profile("minimal")
+        .build()
+        .unwrap();
+
+    assert_eq!(built, Parser::new(RUSTUP_BOOK_SPEC).parse().unwrap());
+}
+
+#[test]
+fn path_equals_parsed() {
+    let built = RustToolchainToml::builder()
+        .with_path("/path/to/local/toolchain")
+        .build()
+        .unwrap();
+
+    assert_eq!(
+        built,
+        Parser::new(RUSTUP_BOOK_LOCAL_TOOLCHAIN).parse().unwrap()
+    );
+}
+
+#[test]
+fn channel_only() {
+    let built = RustToolchainToml::builder()
+        .with_channel("stable")
+        .build()
+        .unwrap();
+
+    let spec = built.toolchain().spec().unwrap();
+    assert_eq!(spec.channel().unwrap().name(), "stable");
+    assert!(spec.components().is_none());
+    assert!(spec.targets().is_none());
+    assert!(spec.profile().is_none());
+}
+
+#[test]
+fn empty_components() {
+    let built = RustToolchainToml::builder()
+        .with_channel("stable")
+        .with_components(Vec::<String>::new())
+        .build()
+        .unwrap();
+
+    assert_eq!(
+        built.toolchain().spec().unwrap().components(),
+        Some(&[][..])
+    );
+}
+
+#[parameterized(
+    nothing = { RustToolchainToml::builder(), BuildError::MissingChannelOrPath },
+    components_only = { RustToolchainToml::builder().with_component("rustfmt"), BuildError::MissingChannelOrPath },
+    path_with_channel = { RustToolchainToml::builder().with_path("/rust").with_channel("stable"), BuildError::PathWithSpecKeys },
+    path_with_profile = { RustToolchainToml::builder().with_path("/rust").with_profile("minimal"), BuildError::PathWithSpecKeys },
+    empty_path = { RustToolchainToml::builder().with_path(""), BuildError::Empty(Key::Path) },
+    empty_channel = { RustToolchainToml::builder().with_channel(""), BuildError::Empty(Key::Channel) },
+    channel_is_path = { RustToolchainToml::builder().with_channel("/rust"), BuildError::ChannelIsPath },
+    invalid_channel = { RustToolchainToml::builder().with_channel("nightly 2020-07-10"), BuildError::InvalidChannel(GrammarViolation::Whitespace) },
+    empty_component = { RustToolchainToml::builder().with_channel("stable").with_component(" "), BuildError::Empty(Key::Components) },
+    empty_target = { RustToolchainToml::builder().with_channel("stable").with_target(""), BuildError::Empty(Key::Targets) },
+    empty_profile = { RustToolchainToml::builder().with_channel("stable").with_profile(""), BuildError::Empty(Key::Profile) },
+)]
+fn invalid(builder: Builder, expected: BuildError) {
+    assert_eq!(builder.build().unwrap_err(), expected);
+}
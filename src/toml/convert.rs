@@ -0,0 +1,100 @@
+//! Conversions between the legacy and the TOML toolchain file variants.
+
+use crate::legacy::LegacyChannel;
+use crate::toml::{Channel, RustToolchainToml, ToolchainPath, ToolchainSection, ToolchainSpec};
+use crate::{LegacyToolchainFile, ToolchainFile};
+use core::fmt;
+
+#[cfg(all(test, any(feature = "use_toml_edit", feature = "use_toml")))]
+mod tests;
+
+impl From<LegacyToolchainFile> for RustToolchainToml {
+    /// A toolchain name becomes the `channel`, and a toolchain path becomes the `path`.
+    fn from(file: LegacyToolchainFile) -> Self {
+        let toolchain = match file.into_channel() {
+            LegacyChannel::Path(path) => ToolchainSection::Path(ToolchainPath { path }),
+            LegacyChannel::Spec(name) => ToolchainSection::Spec(ToolchainSpec {
+                channel: Some(Channel(name)),
+                components: None,
+                targets: None,
+                profile: None,
+            }),
+        };
+
+        Self { toolchain }
+    }
+}
+
+impl From<ToolchainFile> for RustToolchainToml {
+    fn from(file: ToolchainFile) -> Self {
+        match file {
+            ToolchainFile::Legacy(file) => file.into(),
+            ToolchainFile::Toml(file) => file,
+        }
+    }
+}
+
+impl TryFrom<RustToolchainToml> for LegacyToolchainFile {
+    type Error = ConvertError;
+
+    /// Succeeds only when the conversion is lossless: the `[toolchain]` table must hold nothing but a
+    /// `channel`, or an absolute `path`.
+    fn try_from(file: RustToolchainToml) -> Result<Self, Self::Error> {
+        let channel = match file.toolchain {
+            ToolchainSection::Path(ToolchainPath { path }) if path.is_relative() => {
+                return Err(ConvertError::RelativePath)
+            }
+            ToolchainSection::Path(ToolchainPath { path }) => LegacyChannel::Path(path),
+            ToolchainSection::Spec(spec) => {
+                if spec.components.is_some() {
+                    return Err(ConvertError::Components);
+                }
+
+                if spec.targets.is_some() {
+                    return Err(ConvertError::Targets);
+                }
+
+                if spec.profile.is_some() {
+                    return Err(ConvertError::Profile);
+                }
+
+                match spec.channel {
+                    Some(Channel(name)) => LegacyChannel::Spec(name),
+                    None => return Err(ConvertError::MissingChannel),
+                }
+            }
+        };
+
+        Ok(LegacyToolchainFile::new(channel))
+    }
+}
+
+/// The reason a [`RustToolchainToml`] can not be converted to a [`LegacyToolchainFile`] without
+/// losing information.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConvertError {
+    /// The file specifies `components`, which may be empty.
+    Components,
+    /// The file specifies `targets`, which may be empty.
+    Targets,
+    Profile,
+    MissingChannel,
+    /// A relative path, which a legacy toolchain file would read as a toolchain name.
+    RelativePath,
+}
+
+impl fmt::Display for ConvertError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Components => "Unable to convert to a legacy toolchain file: 'components' can not be specified in a legacy toolchain file",
+            Self::Targets => "Unable to convert to a legacy toolchain file: 'targets' can not be specified in a legacy toolchain file",
+            Self::Profile => "Unable to convert to a legacy toolchain file: 'profile' can not be specified in a legacy toolchain file",
+            Self::MissingChannel => "Unable to convert to a legacy toolchain file: neither 'channel' nor 'path' is specified",
+            Self::RelativePath => "Unable to convert to a legacy toolchain file: a relative 'path' would be read as a toolchain name",
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ConvertError {}
@@ -0,0 +1,56 @@
+use crate::legacy::{self, LegacyChannel};
+use crate::toml::{self, ConvertError, RustToolchainToml};
+use crate::{LegacyToolchainFile, PortablePath, ToolchainFile};
+use yare::parameterized;
+
+#[parameterized(
+    spec = { "nightly-2020-07-10", "[toolchain]\nchannel = \"nightly-2020-07-10\"\n" },
+    path = { "/opt/rust", "[toolchain]\npath = \"/opt/rust\"\n" },
+    windows_path = { r"C:\toolchains\rust", "[toolchain]\npath = 'C:\\toolchains\\rust'\n" },
+)]
+fn from_legacy(legacy: &str, toml: &str) {
+    let legacy = legacy::Parser::new(legacy).parse().unwrap();
+    let expected = toml::Parser::new(toml).parse().unwrap();
+
+    assert_eq!(RustToolchainToml::from(legacy), expected);
+}
+
+#[parameterized(
+    spec = { "[toolchain]\nchannel = \"stable\"\n", LegacyChannel::Spec("stable".to_string()) },
+    path = { "[toolchain]\npath = \"/opt/rust\"\n", LegacyChannel::Path(PortablePath::from("/opt/rust")) },
+)]
+fn to_legacy(toml: &str, expected: LegacyChannel) {
+    let file = toml::Parser::new(toml).parse().unwrap();
+
+    let legacy = LegacyToolchainFile::try_from(file.clone()).unwrap();
+    assert_eq!(legacy.channel(), &expected);
+    assert_eq!(RustToolchainToml::from(legacy), file);
+}
+
+#[parameterized(
+    components = { "[toolchain]\nchannel = \"stable\"\ncomponents = [\"clippy\"]\n", ConvertError::Components },
+    empty_components = { "[toolchain]\nchannel = \"stable\"\ncomponents = []\n", ConvertError::Components },
+    targets = { "[toolchain]\nchannel = \"stable\"\ntargets = [\"wasm32-unknown-unknown\"]\n", ConvertError::Targets },
+    profile = { "[toolchain]\nchannel = \"stable\"\nprofile = \"minimal\"\n", ConvertError::Profile },
+    missing_channel = { "[toolchain]\n", ConvertError::MissingChannel },
+    relative_path = { "[toolchain]\npath = \"toolchains/rust\"\n", ConvertError::RelativePath },
+)]
+fn to_legacy_is_lossy(toml: &str, expected: ConvertError) {
+    let file = toml::Parser::new(toml).parse().unwrap();
+
+    assert_eq!(LegacyToolchainFile::try_from(file).unwrap_err(), expected);
+}
+
+#[test]
+fn from_toolchain_file() {
+    let toml = toml::Parser::new("[toolchain]\nchannel = \"stable\"\n")
+        .parse()
+        .unwrap();
+    let legacy = legacy::Parser::new("stable").parse().unwrap();
+
+    assert_eq!(
+        RustToolchainToml::from(ToolchainFile::Toml(toml.clone())),
+        toml
+    );
+    assert_eq!(RustToolchainToml::from(ToolchainFile::Legacy(legacy)), toml);
+}
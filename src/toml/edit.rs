@@ -0,0 +1,216 @@
+//! Format-preserving editing of TOML toolchain files, backed by `toml_edit`.
+
+use crate::toml::{Parser, ParserError, RustToolchainToml, TomlError};
+use crate::PortablePath;
+use std::fmt;
+use std::str::FromStr;
+use toml_edit::{Array, Item, TableLike, Value};
+
+#[cfg(test)]
+mod tests;
+
+const SPEC_KEYS: [&str; 4] = ["channel", "components", "targets", "profile"];
+
+/// An editable TOML toolchain file.
+///
+/// Edits only replace the edited values: comments, the order of keys and whitespace elsewhere in
+/// the document are kept as is.
+#[derive(Clone, Debug)]
+pub struct Document {
+    document: toml_edit::Document,
+}
+
+impl Document {
+    /// Parse the content, which must be a valid TOML toolchain file.
+    pub fn parse(content: &str) -> Result<Self, ParserError> {
+        let document = parse_document(content)?;
+
+        Ok(Self { document })
+    }
+
+    /// Parse the edited document into the toolchain file model.
+    pub fn toolchain(&self) -> Result<RustToolchainToml, ParserError> {
+        Parser::new(&self.document.to_string()).parse()
+    }
+
+    /// Set the channel. Since a path can not be combined with a channel, the `path` is removed.
+    pub fn set_channel(&mut self, channel: &str) {
+        let table = self.toolchain_mut();
+
+        table.remove("path");
+        set_string(table, "channel", channel);
+    }
+
+    /// Set the profile. Since a path can not be combined with a profile, the `path` is removed.
+    pub fn set_profile(&mut self, profile: &str) {
+        let table = self.toolchain_mut();
+
+        table.remove("path");
+        set_string(table, "profile", profile);
+    }
+
+    /// Replace the channel, components, targets and profile with the given toolchain path.
+    pub fn set_path(&mut self, path: &PortablePath) {
+        let table = self.toolchain_mut();
+
+        for key in SPEC_KEYS {
+            table.remove(key);
+        }
+
+        set_string(table, "path", path.as_str());
+    }
+
+    /// Add the component, unless it is already present. Returns whether the component was added.
+    pub fn add_component(&mut self, component: &str) -> bool {
+        let table = self.toolchain_mut();
+
+        table.remove("path");
+        add_to_array(table, "components", component)
+    }
+
+    /// Remove the component, if it is present. Returns whether the component was removed.
+    pub fn remove_component(&mut self, component: &str) -> bool {
+        remove_from_array(self.toolchain_mut(), "components", component)
+    }
+
+    /// Add the target, unless it is already present. Returns whether the target was added.
+    pub fn add_target(&mut self, target: &str) -> bool {
+        let table = self.toolchain_mut();
+
+        table.remove("path");
+        add_to_array(table, "targets", target)
+    }
+
+    /// Remove the target, if it is present. Returns whether the target was removed.
+    pub fn remove_target(&mut self, target: &str) -> bool {
+        remove_from_array(self.toolchain_mut(), "targets", target)
+    }
+
+    fn toolchain_mut(&mut self) -> &mut dyn TableLike {
+        self.document
+            .entry("toolchain")
+            .or_insert_with(toml_edit::table)
+            .as_table_like_mut()
+            .expect("the toolchain key holds a table, as verified when parsing")
+    }
+}
+
+impl FromStr for Document {
+    type Err = ParserError;
+
+    fn from_str(content: &str) -> Result<Self, Self::Err> {
+        Self::parse(content)
+    }
+}
+
+impl fmt::Display for Document {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.document)
+    }
+}
+
+/// Serialized as the TOML content of the document.
+impl serde::Serialize for Document {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Document {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let content = String::deserialize(deserializer)?;
+
+        Self::parse(&content).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Parse the content into a `toml_edit` document, after verifying it is a valid toolchain file.
+pub(super) fn parse_document(content: &str) -> Result<toml_edit::Document, ParserError> {
+    Parser::new(content).parse()?;
+
+    content
+        .parse::<toml_edit::Document>()
+        .map_err(|err| TomlError {
+            message: err.to_string(),
+            span: err.span(),
+        })
+        .map_err(ParserError::TomlParse)
+}
+
+/// Replace the value of the key, while keeping the whitespace and comments surrounding the value.
+fn set_string(table: &mut dyn TableLike, key: &str, value: &str) {
+    match table.get_mut(key).and_then(Item::as_value_mut) {
+        Some(existing) => {
+            let decor = existing.decor().clone();
+            *existing = Value::from(value);
+            *existing.decor_mut() = decor;
+        }
+        None => {
+            table.insert(key, toml_edit::value(value));
+        }
+    }
+}
+
+fn add_to_array(table: &mut dyn TableLike, key: &str, name: &str) -> bool {
+    let array = table
+        .entry(key)
+        .or_insert_with(|| toml_edit::value(Array::new()))
+        .as_array_mut()
+        .expect("the key holds an array, as verified when parsing");
+
+    if array.iter().any(|value| value.as_str() == Some(name)) {
+        return false;
+    }
+
+    // Format the new value like the last value, unless it is the first value, which lacks the
+    // leading whitespace of the values which follow it. The new value takes over the whitespace
+    // between the last value and the end of the array.
+    match array.len() {
+        0 | 1 => array.push(name),
+        len => {
+            let inner_suffix = array.get(len - 2).and_then(|v| v.decor().suffix()).cloned();
+            let last = array
+                .get_mut(len - 1)
+                .expect("the array holds at least two values");
+            let mut value = Value::from(name);
+            *value.decor_mut() = last.decor().clone();
+
+            match inner_suffix {
+                Some(suffix) => last.decor_mut().set_suffix(suffix),
+                None => last.decor_mut().set_suffix(""),
+            }
+
+            array.push_formatted(value);
+        }
+    }
+
+    true
+}
+
+fn remove_from_array(table: &mut dyn TableLike, key: &str, name: &str) -> bool {
+    let Some(array) = table.get_mut(key).and_then(Item::as_array_mut) else {
+        return false;
+    };
+
+    let Some(index) = array.iter().position(|value| value.as_str() == Some(name)) else {
+        return false;
+    };
+
+    let removed = array.remove(index);
+
+    // A value which becomes the first or the last value takes over the leading or trailing
+    // whitespace of the removed value.
+    if index == 0 {
+        if let (Some(first), Some(prefix)) = (array.get_mut(0), removed.decor().prefix()) {
+            first.decor_mut().set_prefix(prefix.clone());
+        }
+    }
+
+    if index == array.len() && index > 0 {
+        if let (Some(last), Some(suffix)) = (array.get_mut(index - 1), removed.decor().suffix()) {
+            last.decor_mut().set_suffix(suffix.clone());
+        }
+    }
+
+    true
+}
@@ -0,0 +1,161 @@
+use crate::toml::edit::Document;
+use crate::toml::ParserError;
+use crate::PortablePath;
+
+const DOCUMENT: &str = r#"# Pinned for reproducible builds.
+[toolchain]
+channel = "nightly-2020-07-10" # bumped monthly
+components = [ "rustfmt", "rustc-dev" ]
+targets = [
+    "wasm32-unknown-unknown",
+    "thumbv2-none-eabi",
+]
+
+# Keep the download small.
+profile = "minimal"
+"#;
+
+fn document() -> Document {
+    Document::parse(DOCUMENT).unwrap()
+}
+
+#[test]
+fn unchanged() {
+    assert_eq!(document().to_string(), DOCUMENT);
+}
+
+#[test]
+fn rejects_invalid_toolchain_file() {
+    let result = Document::parse("[toolchain]\nchannel = 1\n");
+
+    assert!(matches!(result, Err(ParserError::TomlParse(_))));
+}
+
+#[test]
+fn set_channel() {
+    let mut document = document();
+    document.set_channel("1.70.0");
+
+    assert_eq!(
+        document.to_string(),
+        DOCUMENT.replace(r#""nightly-2020-07-10""#, r#""1.70.0""#)
+    );
+    assert_eq!(
+        document
+            .toolchain()
+            .unwrap()
+            .toolchain()
+            .spec()
+            .unwrap()
+            .channel()
+            .unwrap()
+            .name(),
+        "1.70.0"
+    );
+}
+
+#[test]
+fn set_profile() {
+    let mut document = document();
+    document.set_profile("default");
+
+    assert_eq!(
+        document.to_string(),
+        DOCUMENT.replace(r#""minimal""#, r#""default""#)
+    );
+}
+
+#[test]
+fn add_component() {
+    let mut document = document();
+
+    assert!(document.add_component("clippy"));
+    assert!(!document.add_component("clippy"));
+    assert_eq!(
+        document.to_string(),
+        DOCUMENT.replace(
+            r#"[ "rustfmt", "rustc-dev" ]"#,
+            r#"[ "rustfmt", "rustc-dev", "clippy" ]"#
+        )
+    );
+}
+
+#[test]
+fn remove_component() {
+    let mut document = document();
+
+    assert!(document.remove_component("rustfmt"));
+    assert!(!document.remove_component("rustfmt"));
+    assert_eq!(
+        document.to_string(),
+        DOCUMENT.replace(r#"[ "rustfmt", "rustc-dev" ]"#, r#"[ "rustc-dev" ]"#)
+    );
+}
+
+#[test]
+fn remove_last_component() {
+    let mut document = document();
+
+    assert!(document.remove_component("rustc-dev"));
+    assert_eq!(
+        document.to_string(),
+        DOCUMENT.replace(r#"[ "rustfmt", "rustc-dev" ]"#, r#"[ "rustfmt" ]"#)
+    );
+}
+
+#[test]
+fn add_target_to_multiline_array() {
+    let mut document = document();
+
+    assert!(document.add_target("x86_64-unknown-linux-musl"));
+    assert_eq!(
+        document.to_string(),
+        DOCUMENT.replace(
+            "    \"thumbv2-none-eabi\",\n",
+            "    \"thumbv2-none-eabi\",\n    \"x86_64-unknown-linux-musl\",\n"
+        )
+    );
+}
+
+#[test]
+fn remove_target() {
+    let mut document = document();
+
+    assert!(document.remove_target("thumbv2-none-eabi"));
+    assert_eq!(
+        document.to_string(),
+        DOCUMENT.replace("    \"thumbv2-none-eabi\",\n", "")
+    );
+}
+
+#[test]
+fn add_key() {
+    let mut document =
+        Document::parse("[toolchain] # the toolchain\nchannel = \"stable\"\n").unwrap();
+
+    assert!(document.add_component("rustfmt"));
+    assert_eq!(
+        document.to_string(),
+        "[toolchain] # the toolchain\nchannel = \"stable\"\ncomponents = [\"rustfmt\"]\n"
+    );
+}
+
+#[test]
+fn set_path() {
+    let mut document = document();
+    document.set_path(&PortablePath::from("/opt/rust"));
+
+    assert_eq!(
+        document.to_string(),
+        "# Pinned for reproducible builds.\n[toolchain]\npath = \"/opt/rust\"\n"
+    );
+    assert!(document.toolchain().unwrap().toolchain().path().is_some());
+}
+
+#[test]
+fn switch_from_path_to_channel() {
+    let mut document = Document::parse("[toolchain]\npath = \"/opt/rust\"\n").unwrap();
+    document.set_channel("stable");
+
+    assert_eq!(document.to_string(), "[toolchain]\nchannel = \"stable\"\n");
+}
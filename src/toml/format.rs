@@ -0,0 +1,365 @@
+//! Canonical formatting of TOML toolchain files, backed by `toml_edit`.
+//!
+//! In the canonical layout, the keys of the `[toolchain]` table are ordered as `path`, `channel`,
+//! `components`, `targets` and `profile`, followed by unknown keys in their original order. Arrays
+//! are sorted and deduplicated, strings use consistent quoting, and blank lines within the table are
+//! removed. Comments are kept: comments on the lines before a key or array value move along with it,
+//! and comments at the end of a line remain at the end of that line.
+
+use crate::toml::edit::parse_document;
+use crate::toml::ParserError;
+use std::fmt;
+use toml_edit::{Array, Decor, Item, Key, RawString, Table, Value};
+
+#[cfg(test)]
+mod tests;
+
+const KEY_ORDER: [&str; 5] = ["path", "channel", "components", "targets", "profile"];
+
+/// Rewrite the content, which must be a valid TOML toolchain file, into the canonical layout.
+pub fn format(content: &str) -> Result<String, ParserError> {
+    let mut document = parse_document(content)?;
+
+    if let Some(toolchain) = document.remove("toolchain") {
+        document.insert("toolchain", Item::Table(canonical_table(toolchain)));
+    }
+
+    Ok(format!("{}\n", document.to_string().trim()))
+}
+
+/// Verify the content is formatted canonically.
+///
+/// Returns `None` if it is, and the difference between the content and the canonical layout if it
+/// is not.
+pub fn check(content: &str) -> Result<Option<Diff>, ParserError> {
+    let formatted = format(content)?;
+
+    if formatted == content {
+        return Ok(None);
+    }
+
+    Ok(Some(Diff::new(content, &formatted)))
+}
+
+fn canonical_table(toolchain: Item) -> Table {
+    let mut source = match toolchain {
+        Item::Table(table) => table,
+        Item::Value(Value::InlineTable(table)) => table.into_table(),
+        _ => unreachable!("the toolchain key holds a table, as verified when parsing"),
+    };
+
+    let mut table = Table::new();
+    table.set_implicit(false);
+    table.set_dotted(false);
+    *table.decor_mut() = source.decor().clone();
+
+    if let Some(position) = source.position() {
+        table.set_position(position);
+    }
+
+    let unknown = source
+        .iter()
+        .map(|(key, _)| key.to_string())
+        .filter(|key| !KEY_ORDER.contains(&key.as_str()))
+        .collect::<Vec<_>>();
+
+    let keys = KEY_ORDER.iter().map(|key| key.to_string()).chain(unknown);
+
+    for key in keys {
+        let Some((key, item)) = source.remove_entry(&key) else {
+            continue;
+        };
+
+        let prefix = comment_lines(key.decor().prefix().and_then(RawString::as_str))
+            .map(|comment| format!("{comment}\n"))
+            .collect::<String>();
+
+        // The decor of the key of a table is placed within its header, so only value items are
+        // spaced out.
+        let (key, item) = match item {
+            Item::Value(value) => (
+                Key::new(key.get()).with_decor(Decor::new(prefix, " ")),
+                Item::Value(canonical_value(value)),
+            ),
+            item => (Key::new(key.get()), item),
+        };
+
+        table.insert_formatted(&key, item);
+    }
+
+    table
+}
+
+fn canonical_value(value: Value) -> Value {
+    let comment = comment_lines(value.decor().suffix().and_then(RawString::as_str))
+        .next()
+        .map(|comment| format!(" {comment}"))
+        .unwrap_or_default();
+
+    let mut value = match value {
+        Value::String(string) => Value::from(string.value().as_str()),
+        Value::Array(array) if array.iter().all(|value| value.as_str().is_some()) => {
+            Value::Array(canonical_array(array))
+        }
+        value => value,
+    };
+
+    *value.decor_mut() = Decor::new(" ", comment);
+    value
+}
+
+/// Sort and deduplicate the array. When any of its values is commented, every value is placed on its
+/// own line, preceded by the comments on the lines before it, and followed by the comment at the end
+/// of its line.
+fn canonical_array(array: Array) -> Array {
+    let mut entries: Vec<Entry> = Vec::new();
+
+    for value in array.iter() {
+        let (same_line, next_lines) = split_first_line(value.decor().prefix());
+        let mut leading = Vec::new();
+
+        // A comment after the comma belongs to the line of the previous value.
+        match entries.last_mut() {
+            Some(previous) => previous
+                .trailing
+                .extend(comment_lines(same_line).map(String::from)),
+            None => leading.extend(comment_lines(same_line).map(String::from)),
+        }
+
+        leading.extend(comment_lines(next_lines).map(String::from));
+
+        entries.push(Entry {
+            value: value.as_str().unwrap_or_default().to_string(),
+            leading,
+            trailing: comment_lines(value.decor().suffix().and_then(RawString::as_str))
+                .map(String::from)
+                .collect(),
+        });
+    }
+
+    let (same_line, next_lines) = split_first_line(Some(array.trailing()));
+
+    if let Some(last) = entries.last_mut() {
+        last.trailing
+            .extend(comment_lines(same_line).map(String::from));
+    }
+
+    let trailing = comment_lines(next_lines)
+        .map(String::from)
+        .collect::<Vec<_>>();
+
+    entries.sort_by(|left, right| left.value.cmp(&right.value));
+    entries.dedup_by(|duplicate, kept| {
+        if duplicate.value == kept.value {
+            kept.leading.append(&mut duplicate.leading);
+            kept.trailing.append(&mut duplicate.trailing);
+        }
+
+        duplicate.value == kept.value
+    });
+
+    let mut canonical = Array::new();
+
+    let is_commented = |entry: &Entry| !entry.leading.is_empty() || !entry.trailing.is_empty();
+
+    if trailing.is_empty() && !entries.iter().any(is_commented) {
+        for entry in entries {
+            canonical.push(entry.value);
+        }
+
+        return canonical;
+    }
+
+    let mut end_of_line = String::new();
+
+    for entry in entries {
+        let leading = entry
+            .leading
+            .iter()
+            .map(|comment| format!("\n    {comment}"))
+            .collect::<String>();
+
+        let prefix = format!("{end_of_line}{leading}\n    ");
+        canonical.push_formatted(Value::from(entry.value).decorated(prefix, ""));
+
+        end_of_line = entry
+            .trailing
+            .iter()
+            .map(|comment| format!(" {comment}"))
+            .collect();
+    }
+
+    let trailing = trailing
+        .iter()
+        .map(|comment| format!("\n    {comment}"))
+        .collect::<String>();
+
+    canonical.set_trailing_comma(true);
+    canonical.set_trailing(format!("{end_of_line}{trailing}\n"));
+    canonical
+}
+
+struct Entry {
+    value: String,
+    /// The comments on the lines before the value.
+    leading: Vec<String>,
+    /// The comments at the end of the line of the value.
+    trailing: Vec<String>,
+}
+
+/// Split whitespace and comments into the part on the current line, and the part on the lines which
+/// follow it.
+fn split_first_line(raw: Option<&RawString>) -> (Option<&str>, Option<&str>) {
+    match raw.and_then(RawString::as_str) {
+        Some(raw) => match raw.split_once('\n') {
+            Some((same_line, next_lines)) => (Some(same_line), Some(next_lines)),
+            None => (Some(raw), None),
+        },
+        None => (None, None),
+    }
+}
+
+/// The comments within the given whitespace and comments, without their surrounding whitespace.
+fn comment_lines<'a>(raw: impl Into<Option<&'a str>>) -> impl Iterator<Item = &'a str> {
+    raw.into()
+        .unwrap_or_default()
+        .lines()
+        .map(str::trim)
+        .filter(|line| line.starts_with('#'))
+}
+
+/// The difference between content and its canonical layout, by line.
+///
+/// Each line includes its line ending, if any, so a difference in line endings, or a missing newline
+/// at the end of the content, shows up as a changed line.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(transparent)]
+pub struct Diff {
+    lines: Vec<DiffLine>,
+}
+
+impl Diff {
+    /// Compute the difference, based on the longest common subsequence of lines.
+    ///
+    /// The lines which both start or end with are unchanged. If the lines in between are too many
+    /// to compare in bounded memory, they are all reported as removed and added.
+    fn new(original: &str, formatted: &str) -> Self {
+        let original = original.split_inclusive('\n').collect::<Vec<_>>();
+        let formatted = formatted.split_inclusive('\n').collect::<Vec<_>>();
+
+        let prefix = original
+            .iter()
+            .zip(&formatted)
+            .take_while(|(original, formatted)| original == formatted)
+            .count();
+        let suffix = original[prefix..]
+            .iter()
+            .rev()
+            .zip(formatted[prefix..].iter().rev())
+            .take_while(|(original, formatted)| original == formatted)
+            .count();
+
+        let unchanged = |lines: &[&str]| {
+            lines
+                .iter()
+                .map(|line| DiffLine::Unchanged(line.to_string()))
+                .collect::<Vec<_>>()
+        };
+
+        let mut lines = unchanged(&original[..prefix]);
+        lines.extend(changed_lines(
+            &original[prefix..original.len() - suffix],
+            &formatted[prefix..formatted.len() - suffix],
+        ));
+        lines.extend(unchanged(&original[original.len() - suffix..]));
+
+        Self { lines }
+    }
+
+    pub fn lines(&self) -> &[DiffLine] {
+        &self.lines
+    }
+}
+
+/// The upper bound of the number of entries in the table of [`changed_lines`].
+const MAX_TABLE_SIZE: usize = 1 << 20;
+
+/// The difference between the lines, based on their longest common subsequence.
+fn changed_lines(original: &[&str], formatted: &[&str]) -> Vec<DiffLine> {
+    let table_size = (original.len() + 1).saturating_mul(formatted.len() + 1);
+
+    if table_size > MAX_TABLE_SIZE {
+        return original
+            .iter()
+            .map(|line| DiffLine::Removed(line.to_string()))
+            .chain(
+                formatted
+                    .iter()
+                    .map(|line| DiffLine::Added(line.to_string())),
+            )
+            .collect();
+    }
+
+    // The length of the longest common subsequence of the remaining lines, from each position.
+    let mut lengths = vec![vec![0usize; formatted.len() + 1]; original.len() + 1];
+
+    for i in (0..original.len()).rev() {
+        for j in (0..formatted.len()).rev() {
+            lengths[i][j] = if original[i] == formatted[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let mut lines = Vec::new();
+
+    while i < original.len() || j < formatted.len() {
+        if i < original.len() && j < formatted.len() && original[i] == formatted[j] {
+            lines.push(DiffLine::Unchanged(original[i].to_string()));
+            i += 1;
+            j += 1;
+        } else if i < original.len()
+            && (j == formatted.len() || lengths[i + 1][j] >= lengths[i][j + 1])
+        {
+            lines.push(DiffLine::Removed(original[i].to_string()));
+            i += 1;
+        } else {
+            lines.push(DiffLine::Added(formatted[j].to_string()));
+            j += 1;
+        }
+    }
+
+    lines
+}
+
+/// Renders the difference in the style of a unified diff, without hunk headers.
+impl fmt::Display for Diff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for line in &self.lines {
+            let (marker, line) = match line {
+                DiffLine::Unchanged(line) => (' ', line),
+                DiffLine::Removed(line) => ('-', line),
+                DiffLine::Added(line) => ('+', line),
+            };
+
+            write!(f, "{marker}{line}")?;
+
+            if !line.ends_with('\n') {
+                f.write_str("\n\\ No newline at end of file\n")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "kind", content = "value", rename_all = "snake_case")]
+pub enum DiffLine {
+    Unchanged(String),
+    Removed(String),
+    Added(String),
+}
@@ -0,0 +1,208 @@
+use crate::toml::format::{check, format, Diff, DiffLine};
+use crate::toml::ParserError;
+use yare::parameterized;
+
+const CANONICAL: &str = r#"# Pinned for reproducible builds.
+[toolchain]
+channel = "nightly-2020-07-10" # bumped monthly
+components = ["rustc-dev", "rustfmt"]
+targets = ["thumbv2-none-eabi", "wasm32-unknown-unknown"]
+# Keep the download small.
+profile = "minimal"
+"#;
+
+#[parameterized(
+    canonical = { CANONICAL },
+    key_order = { r#"# Pinned for reproducible builds.
+[toolchain]
+# Keep the download small.
+profile = "minimal"
+targets = ["thumbv2-none-eabi", "wasm32-unknown-unknown"]
+components = ["rustc-dev", "rustfmt"]
+channel = "nightly-2020-07-10" # bumped monthly
+"# },
+    unsorted_arrays = { r#"# Pinned for reproducible builds.
+[toolchain]
+channel = "nightly-2020-07-10" # bumped monthly
+components = ["rustfmt", "rustc-dev", "rustfmt"]
+targets = [
+    "wasm32-unknown-unknown",
+    "thumbv2-none-eabi",
+]
+# Keep the download small.
+profile = "minimal"
+"# },
+    quoting_and_spacing = { r#"# Pinned for reproducible builds.
+[toolchain]
+
+channel='nightly-2020-07-10'   # bumped monthly
+components   =   [ 'rustc-dev','rustfmt' ]
+targets = ["thumbv2-none-eabi", "wasm32-unknown-unknown"]
+
+# Keep the download small.
+profile = "minimal"
+
+"# },
+)]
+fn format_to_canonical(content: &str) {
+    assert_eq!(format(content).unwrap(), CANONICAL);
+}
+
+#[test]
+fn inline_table() {
+    let content = "toolchain = { channel = \"stable\", components = [\"rustfmt\", \"clippy\"] }\n";
+
+    assert_eq!(
+        format(content).unwrap(),
+        "[toolchain]\nchannel = \"stable\"\ncomponents = [\"clippy\", \"rustfmt\"]\n"
+    );
+}
+
+#[test]
+fn commented_array_values() {
+    let content = r#"[toolchain]
+channel = "stable"
+targets = [
+    # Linux
+    "x86_64-unknown-linux-gnu",
+    "aarch64-apple-darwin", # macOS
+    # more to come
+]
+"#;
+
+    let formatted = format(content).unwrap();
+
+    assert_eq!(format(&formatted).unwrap(), formatted);
+    assert_eq!(
+        formatted,
+        r#"[toolchain]
+channel = "stable"
+targets = [
+    "aarch64-apple-darwin", # macOS
+    # Linux
+    "x86_64-unknown-linux-gnu",
+    # more to come
+]
+"#
+    );
+}
+
+#[test]
+fn path() {
+    let content = "[toolchain]\n path = \"/opt/rust\"\n";
+
+    assert_eq!(
+        format(content).unwrap(),
+        "[toolchain]\npath = \"/opt/rust\"\n"
+    );
+}
+
+#[test]
+fn keeps_unknown_keys_and_tables() {
+    let content = "[toolchain]\nunknown = 1\nchannel = \"stable\"\n\n[other]\nkey = 'value'\n";
+
+    assert_eq!(
+        format(content).unwrap(),
+        "[toolchain]\nchannel = \"stable\"\nunknown = 1\n\n[other]\nkey = 'value'\n"
+    );
+}
+
+#[test]
+fn is_idempotent() {
+    let formatted = format(CANONICAL).unwrap();
+
+    assert_eq!(format(&formatted).unwrap(), formatted);
+}
+
+#[test]
+fn rejects_invalid_toolchain_file() {
+    assert!(matches!(
+        format("[toolchain]\nchannel = [1]\n"),
+        Err(ParserError::TomlParse(_))
+    ));
+}
+
+#[test]
+fn check_formatted() {
+    assert_eq!(check(CANONICAL).unwrap(), None);
+}
+
+#[test]
+fn check_unformatted() {
+    let diff = check("[toolchain]\ncomponents = [\"rustfmt\", \"clippy\"]\nchannel = \"stable\"\n")
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(
+        diff.lines(),
+        &[
+            DiffLine::Unchanged("[toolchain]\n".to_string()),
+            DiffLine::Removed("components = [\"rustfmt\", \"clippy\"]\n".to_string()),
+            DiffLine::Unchanged("channel = \"stable\"\n".to_string()),
+            DiffLine::Added("components = [\"clippy\", \"rustfmt\"]\n".to_string()),
+        ]
+    );
+    assert_eq!(
+        diff.to_string(),
+        " [toolchain]\n-components = [\"rustfmt\", \"clippy\"]\n channel = \"stable\"\n+components = [\"clippy\", \"rustfmt\"]\n"
+    );
+}
+
+#[parameterized(
+    sub_table = { "[toolchain]\nchannel = \"stable\"\n\n[toolchain.extra]\nkey = 1\n" },
+    array_of_tables = { "[toolchain]\nchannel = \"stable\"\n\n[[toolchain.extra]]\nkey = 1\n" },
+)]
+fn keeps_sub_table_headers(content: &str) {
+    assert_eq!(format(content).unwrap(), content);
+}
+
+#[test]
+fn check_missing_newline_at_end() {
+    let diff = check("[toolchain]\nchannel = \"stable\"").unwrap().unwrap();
+
+    assert_eq!(
+        diff.lines(),
+        &[
+            DiffLine::Unchanged("[toolchain]\n".to_string()),
+            DiffLine::Removed("channel = \"stable\"".to_string()),
+            DiffLine::Added("channel = \"stable\"\n".to_string()),
+        ]
+    );
+    assert_eq!(
+        diff.to_string(),
+        " [toolchain]\n-channel = \"stable\"\n\\ No newline at end of file\n+channel = \"stable\"\n"
+    );
+}
+
+#[test]
+fn check_crlf_line_endings() {
+    let diff = check("[toolchain]\r\nchannel = \"stable\"\r\n")
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(
+        diff.lines(),
+        &[
+            DiffLine::Removed("[toolchain]\r\n".to_string()),
+            DiffLine::Removed("channel = \"stable\"\r\n".to_string()),
+            DiffLine::Added("[toolchain]\n".to_string()),
+            DiffLine::Added("channel = \"stable\"\n".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn diff_of_many_lines_in_bounded_memory() {
+    let diff = Diff::new(
+        &format!("[toolchain]\n{}", "a\n".repeat(2000)),
+        &format!("[toolchain]\n{}", "b\n".repeat(2000)),
+    );
+
+    assert_eq!(diff.lines().len(), 4001);
+    assert_eq!(
+        diff.lines()[0],
+        DiffLine::Unchanged("[toolchain]\n".to_string())
+    );
+    assert_eq!(diff.lines()[1], DiffLine::Removed("a\n".to_string()));
+    assert_eq!(diff.lines()[4000], DiffLine::Added("b\n".to_string()));
+}
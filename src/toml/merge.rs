@@ -0,0 +1,170 @@
+//! Merging toolchain specifications, like the toolchain files of crates which are combined into a
+//! single workspace.
+
+use crate::toml::normalize::{channel_name, sorted};
+use crate::toml::{release, Channel, Profile, RustToolchainToml, ToolchainSection, ToolchainSpec};
+use crate::PortablePath;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::fmt;
+
+#[cfg(test)]
+mod tests;
+
+/// The profiles known to rustup, from the one which installs the fewest components, to the one
+/// which installs the most.
+const PROFILES: [&str; 3] = ["minimal", "default", "complete"];
+
+/// How to resolve two different channels when merging.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChannelPolicy {
+    /// Reject the merge.
+    #[default]
+    Fail,
+    /// Pick the newest release, like `1.72.0` over `1.70.0`, or `nightly-2023-06-01` over
+    /// `nightly-2023-01-01`.
+    Newest,
+    /// Pick the oldest release.
+    Oldest,
+    /// Pick the channel of the left-hand side of the merge.
+    PreferLeft,
+}
+
+impl ToolchainSpec {
+    /// Merge both specifications.
+    ///
+    /// The result holds the normalized union of the components and targets (see
+    /// [`ToolchainSpec::normalize`]), and the profile which installs the most components, in the
+    /// order `minimal`, `default` and `complete`. Channels are compared after normalization, and
+    /// different channels are resolved according to the given policy, but the merged channel is
+    /// kept as written.
+    pub fn merge(&self, other: &Self, policy: ChannelPolicy) -> Result<Self, MergeError> {
+        let (left, right) = (
+            self.normalize_except_channel(),
+            other.normalize_except_channel(),
+        );
+
+        let channel = match (left.channel, right.channel) {
+            (Some(left), Some(right)) => Some(merge_channel(left, right, policy)?),
+            (left, right) => left.or(right),
+        };
+
+        let profile = match (left.profile, right.profile) {
+            (Some(left), Some(right)) => Some(merge_profile(left, right)?),
+            (left, right) => left.or(right),
+        };
+
+        Ok(Self {
+            channel,
+            components: sorted(union(left.components, right.components)),
+            targets: sorted(union(left.targets, right.targets)),
+            profile,
+        })
+    }
+}
+
+impl RustToolchainToml {
+    /// Merge both toolchain files (see [`ToolchainSpec::merge`]).
+    ///
+    /// A toolchain path can only be merged with the same path, unless the policy is
+    /// [`ChannelPolicy::PreferLeft`], and never with a toolchain specification.
+    pub fn merge(&self, other: &Self, policy: ChannelPolicy) -> Result<Self, MergeError> {
+        let toolchain = match (&self.toolchain, &other.toolchain) {
+            (ToolchainSection::Spec(left), ToolchainSection::Spec(right)) => {
+                ToolchainSection::Spec(left.merge(right, policy)?)
+            }
+            (ToolchainSection::Path(left), ToolchainSection::Path(right))
+                if left == right || policy == ChannelPolicy::PreferLeft =>
+            {
+                ToolchainSection::Path(left.clone())
+            }
+            (ToolchainSection::Path(left), ToolchainSection::Path(right)) => {
+                return Err(MergeError::PathConflict {
+                    left: left.path.clone(),
+                    right: right.path.clone(),
+                })
+            }
+            _ => return Err(MergeError::PathWithSpec),
+        };
+
+        Ok(Self { toolchain })
+    }
+}
+
+fn merge_channel(
+    left: Channel,
+    right: Channel,
+    policy: ChannelPolicy,
+) -> Result<Channel, MergeError> {
+    let (left_name, right_name) = (channel_name(left.name()), channel_name(right.name()));
+
+    if left_name == right_name {
+        return Ok(left);
+    }
+
+    let order = || release::order(&left_name, &right_name);
+
+    match policy {
+        ChannelPolicy::PreferLeft => Ok(left),
+        ChannelPolicy::Newest if order() == Some(Ordering::Less) => Ok(right),
+        ChannelPolicy::Newest if order().is_some() => Ok(left),
+        ChannelPolicy::Oldest if order() == Some(Ordering::Greater) => Ok(right),
+        ChannelPolicy::Oldest if order().is_some() => Ok(left),
+        _ => Err(MergeError::ChannelConflict { left, right }),
+    }
+}
+
+fn merge_profile(left: Profile, right: Profile) -> Result<Profile, MergeError> {
+    if left == right {
+        return Ok(left);
+    }
+
+    let rank = |profile: &Profile| PROFILES.iter().position(|name| *name == profile.name());
+
+    match (rank(&left), rank(&right)) {
+        (Some(l), Some(r)) if l > r => Ok(left),
+        (Some(_), Some(_)) => Ok(right),
+        _ => Err(MergeError::ProfileConflict { left, right }),
+    }
+}
+
+fn union<T>(left: Option<Vec<T>>, right: Option<Vec<T>>) -> Option<Vec<T>> {
+    match (left, right) {
+        (Some(mut left), Some(right)) => {
+            left.extend(right);
+            Some(left)
+        }
+        (left, right) => left.or(right),
+    }
+}
+
+/// The reason two toolchain specifications can not be merged.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "kind", content = "value", rename_all = "snake_case")]
+pub enum MergeError {
+    /// The channels differ, and could not be resolved by the policy.
+    ChannelConflict { left: Channel, right: Channel },
+    /// The profiles differ, and at least one is not known to rustup.
+    ProfileConflict { left: Profile, right: Profile },
+    PathConflict {
+        left: PortablePath,
+        right: PortablePath,
+    },
+    /// A toolchain path can not be merged with a toolchain specification.
+    PathWithSpec,
+}
+
+impl fmt::Display for MergeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ChannelConflict { left, right } => write!(f, "Unable to merge toolchain files: the channels '{}' and '{}' differ", left.name(), right.name()),
+            Self::ProfileConflict { left, right } => write!(f, "Unable to merge toolchain files: the profiles '{}' and '{}' differ", left.name(), right.name()),
+            Self::PathConflict { left, right } => write!(f, "Unable to merge toolchain files: the paths '{left}' and '{right}' differ"),
+            Self::PathWithSpec => f.write_str("Unable to merge toolchain files: a toolchain path can not be merged with a channel, components, targets or profile"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for MergeError {}
@@ -0,0 +1,179 @@
+use crate::test_helpers::build;
+use crate::toml::{ChannelPolicy, MergeError, RustToolchainToml};
+use yare::parameterized;
+
+fn channel(name: &str) -> RustToolchainToml {
+    build(RustToolchainToml::builder().with_channel(name))
+}
+
+fn channel_of(file: &RustToolchainToml) -> &str {
+    file.toolchain().spec().unwrap().channel().unwrap().name()
+}
+
+#[test]
+fn union_of_components_and_targets() {
+    let left = build(
+        RustToolchainToml::builder()
+            .with_channel("stable")
+            .with_components(["rustfmt"])
+            .with_targets(["wasm32-unknown-unknown"]),
+    );
+    let right = build(
+        RustToolchainToml::builder()
+            .with_channel("stable")
+            .with_components(["clippy-preview", "rustfmt"]),
+    );
+
+    let merged = left.merge(&right, ChannelPolicy::Fail).unwrap();
+
+    let expected = build(
+        RustToolchainToml::builder()
+            .with_channel("stable")
+            .with_components(["clippy", "rustfmt"])
+            .with_targets(["wasm32-unknown-unknown"]),
+    );
+    assert_eq!(merged, expected);
+}
+
+#[parameterized(
+    minimal_default = { "minimal", "default", "default" },
+    complete_minimal = { "complete", "minimal", "complete" },
+    same = { "minimal", "minimal", "minimal" },
+)]
+fn largest_profile(left: &str, right: &str, expected: &str) {
+    let left = build(
+        RustToolchainToml::builder()
+            .with_channel("stable")
+            .with_profile(left),
+    );
+    let right = build(
+        RustToolchainToml::builder()
+            .with_channel("stable")
+            .with_profile(right),
+    );
+
+    let merged = left.merge(&right, ChannelPolicy::Fail).unwrap();
+
+    let profile = merged.toolchain().spec().unwrap().profile().unwrap();
+    assert_eq!(profile.name(), expected);
+}
+
+#[test]
+fn unknown_profile_conflicts() {
+    let left = build(
+        RustToolchainToml::builder()
+            .with_channel("stable")
+            .with_profile("custom"),
+    );
+    let right = build(
+        RustToolchainToml::builder()
+            .with_channel("stable")
+            .with_profile("minimal"),
+    );
+
+    assert!(matches!(
+        left.merge(&right, ChannelPolicy::Fail).unwrap_err(),
+        MergeError::ProfileConflict { .. }
+    ));
+}
+
+#[test]
+fn absent_keys_are_taken_from_either_side() {
+    let left = build(
+        RustToolchainToml::builder()
+            .with_channel("stable")
+            .with_target("wasm32-unknown-unknown"),
+    );
+    let right = build(
+        RustToolchainToml::builder()
+            .with_channel("stable")
+            .with_profile("minimal"),
+    );
+
+    let merged = left.merge(&right, ChannelPolicy::Fail).unwrap();
+
+    let expected = build(
+        RustToolchainToml::builder()
+            .with_channel("stable")
+            .with_target("wasm32-unknown-unknown")
+            .with_profile("minimal"),
+    );
+    assert_eq!(merged, expected);
+}
+
+#[parameterized(
+    fail_same = { "1.70", "1.70.0", ChannelPolicy::Fail, "1.70" },
+    same_version = { "1.72", "1.72", ChannelPolicy::Fail, "1.72" },
+    newest_version = { "1.70.0", "1.72.1", ChannelPolicy::Newest, "1.72.1" },
+    newest_version_left = { "1.72.1", "1.70.0", ChannelPolicy::Newest, "1.72.1" },
+    oldest_version = { "1.72.1", "1.70", ChannelPolicy::Oldest, "1.70" },
+    newest_version_numeric = { "1.9.0", "1.10.0", ChannelPolicy::Newest, "1.10.0" },
+    newest_nightly = { "nightly-2023-06-01", "nightly-2023-01-01", ChannelPolicy::Newest, "nightly-2023-06-01" },
+    oldest_nightly = { "nightly-2023-06-01", "nightly-2023-01-01", ChannelPolicy::Oldest, "nightly-2023-01-01" },
+    newest_with_host = { "1.70-x86_64-pc-windows-msvc", "1.71-x86_64-pc-windows-msvc", ChannelPolicy::Newest, "1.71-x86_64-pc-windows-msvc" },
+    prefer_left = { "stable", "nightly", ChannelPolicy::PreferLeft, "stable" },
+)]
+fn channel_policy(left: &str, right: &str, policy: ChannelPolicy, expected: &str) {
+    let merged = channel(left).merge(&channel(right), policy).unwrap();
+
+    assert_eq!(channel_of(&merged), expected);
+}
+
+#[parameterized(
+    fail = { "1.70.0", "1.72.0", ChannelPolicy::Fail },
+    named = { "stable", "nightly", ChannelPolicy::Newest },
+    version_and_named = { "1.70.0", "stable", ChannelPolicy::Oldest },
+    different_channels_with_dates = { "beta-2023-06-01", "nightly-2023-01-01", ChannelPolicy::Newest },
+    different_hosts = { "1.70.0-x86_64-pc-windows-msvc", "1.72.0-x86_64-pc-windows-gnu", ChannelPolicy::Newest },
+)]
+fn channel_conflict(left: &str, right: &str, policy: ChannelPolicy) {
+    let error = channel(left).merge(&channel(right), policy).unwrap_err();
+
+    assert!(matches!(error, MergeError::ChannelConflict { .. }));
+}
+
+#[test]
+fn channel_conflict_message() {
+    let error = channel("stable")
+        .merge(&channel("beta"), ChannelPolicy::Fail)
+        .unwrap_err();
+
+    assert_eq!(
+        error.to_string(),
+        "Unable to merge toolchain files: the channels 'stable' and 'beta' differ"
+    );
+}
+
+#[parameterized(
+    same = { "/opt/rust", "/opt/rust", ChannelPolicy::Fail, Ok("/opt/rust") },
+    prefer_left = { "/opt/rust", "/opt/other", ChannelPolicy::PreferLeft, Ok("/opt/rust") },
+    conflict = { "/opt/rust", "/opt/other", ChannelPolicy::Newest, Err(()) },
+)]
+fn paths(left: &str, right: &str, policy: ChannelPolicy, expected: Result<&str, ()>) {
+    let left = build(RustToolchainToml::builder().with_path(left));
+    let right = build(RustToolchainToml::builder().with_path(right));
+
+    let merged = left.merge(&right, policy);
+
+    match expected {
+        Ok(path) => {
+            let merged = merged.unwrap();
+            let toolchain_path = merged.toolchain().path().unwrap();
+            assert_eq!(toolchain_path.portable_path().as_str(), path);
+        }
+        Err(()) => assert!(matches!(
+            merged.unwrap_err(),
+            MergeError::PathConflict { .. }
+        )),
+    }
+}
+
+#[test]
+fn path_with_spec() {
+    let left = build(RustToolchainToml::builder().with_path("/opt/rust"));
+
+    assert_eq!(
+        left.merge(&channel("stable"), ChannelPolicy::PreferLeft),
+        Err(MergeError::PathWithSpec)
+    );
+}
@@ -0,0 +1,130 @@
+//! Normalization of toolchain specifications, so different spellings of the same toolchain compare
+//! equal.
+
+use crate::toml::{Channel, Component, RustToolchainToml, ToolchainSection, ToolchainSpec};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+#[cfg(test)]
+mod tests;
+
+/// Components which rustup installs under a new name, by their former name.
+const COMPONENT_RENAMES: [(&str, &str); 6] = [
+    ("clippy-preview", "clippy"),
+    ("llvm-tools-preview", "llvm-tools"),
+    ("miri-preview", "miri"),
+    ("rls-preview", "rls"),
+    ("rust-analyzer-preview", "rust-analyzer"),
+    ("rustfmt-preview", "rustfmt"),
+];
+
+impl ToolchainSpec {
+    /// Normalize the specification, so different spellings of the same toolchain are equal:
+    ///
+    /// * a version channel with only a major and minor version, like `1.70`, is completed with a
+    ///   zero patch version, like `1.70.0`;
+    /// * components which were renamed by rustup, like `clippy-preview`, are replaced by their
+    ///   current name, like `clippy`;
+    /// * components and targets are sorted and deduplicated, where an empty array is the same as an
+    ///   absent key.
+    ///
+    /// Note that rustup installs the latest patch release for a `1.70` channel, so `1.72` and
+    /// `1.72.0` are considered equal, even though rustup installs `1.72.1` for the former.
+    pub fn normalize(&self) -> Self {
+        Self {
+            channel: self
+                .channel
+                .as_ref()
+                .map(|channel| Channel(channel_name(channel.name()))),
+            ..self.normalize_except_channel()
+        }
+    }
+
+    /// Normalize the components and targets, but keep the channel as written, since rustup may
+    /// install a different toolchain for a completed version channel.
+    pub(crate) fn normalize_except_channel(&self) -> Self {
+        let components = self.components.as_deref().map(|components| {
+            components
+                .iter()
+                .map(|component| Component(component_name(component.name()).to_string()))
+                .collect()
+        });
+
+        Self {
+            channel: self.channel.clone(),
+            components: sorted(components),
+            targets: sorted(self.targets.clone()),
+            profile: self.profile.clone(),
+        }
+    }
+
+    /// Whether both specifications are equal after normalization (see [`ToolchainSpec::normalize`]).
+    pub fn is_equivalent(&self, other: &Self) -> bool {
+        self.normalize() == other.normalize()
+    }
+}
+
+impl RustToolchainToml {
+    /// Normalize the toolchain specification, if the file specifies one (see
+    /// [`ToolchainSpec::normalize`]). A toolchain path is kept as is.
+    pub fn normalize(&self) -> Self {
+        let toolchain = match &self.toolchain {
+            ToolchainSection::Spec(spec) => ToolchainSection::Spec(spec.normalize()),
+            ToolchainSection::Path(path) => ToolchainSection::Path(path.clone()),
+        };
+
+        Self { toolchain }
+    }
+
+    /// Normalize the toolchain specification like [`RustToolchainToml::normalize`], but keep the
+    /// channel as written (see [`ToolchainSpec::normalize_except_channel`]).
+    pub(crate) fn normalize_except_channel(&self) -> Self {
+        let toolchain = match &self.toolchain {
+            ToolchainSection::Spec(spec) => ToolchainSection::Spec(spec.normalize_except_channel()),
+            ToolchainSection::Path(path) => ToolchainSection::Path(path.clone()),
+        };
+
+        Self { toolchain }
+    }
+
+    /// Whether both files are equal after normalization (see [`RustToolchainToml::normalize`]).
+    pub fn is_equivalent(&self, other: &Self) -> bool {
+        self.normalize() == other.normalize()
+    }
+}
+
+/// Sort and deduplicate the values, where an empty array becomes an absent key.
+pub(crate) fn sorted<T: Ord>(values: Option<Vec<T>>) -> Option<Vec<T>> {
+    let mut values = values.filter(|values| !values.is_empty())?;
+
+    values.sort();
+    values.dedup();
+
+    Some(values)
+}
+
+pub(crate) fn component_name(name: &str) -> &str {
+    COMPONENT_RENAMES
+        .iter()
+        .find(|(former, _)| *former == name)
+        .map_or(name, |(_, current)| current)
+}
+
+/// Complete a `<major>.<minor>` version channel with a zero patch version, while keeping the date
+/// and host which may follow it.
+pub(crate) fn channel_name(name: &str) -> String {
+    let (channel, rest) = match name.split_once('-') {
+        Some((channel, rest)) => (channel, Some(rest)),
+        None => (name, None),
+    };
+
+    let is_number = |part: &str| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit());
+
+    match channel.split_once('.') {
+        Some((major, minor)) if is_number(major) && is_number(minor) => match rest {
+            Some(rest) => alloc::format!("{channel}.0-{rest}"),
+            None => alloc::format!("{channel}.0"),
+        },
+        _ => name.to_string(),
+    }
+}
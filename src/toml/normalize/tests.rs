@@ -0,0 +1,112 @@
+use crate::test_helpers::build;
+use crate::toml::{Builder, RustToolchainToml};
+use yare::parameterized;
+
+#[parameterized(
+    major_minor = { "1.70", "1.70.0" },
+    major_minor_patch = { "1.70.1", "1.70.1" },
+    major_minor_with_host = { "1.70-x86_64-unknown-linux-gnu", "1.70.0-x86_64-unknown-linux-gnu" },
+    stable = { "stable", "stable" },
+    nightly_with_date = { "nightly-2020-07-10", "nightly-2020-07-10" },
+    beta_version = { "1.70.0-beta.1", "1.70.0-beta.1" },
+    not_a_version = { "1.x", "1.x" },
+)]
+fn channel(channel: &str, expected: &str) {
+    let file = build(RustToolchainToml::builder().with_channel(channel)).normalize();
+
+    let normalized = file.toolchain().spec().unwrap().channel().unwrap();
+    assert_eq!(normalized.name(), expected);
+}
+
+#[test]
+fn components_are_renamed_sorted_and_deduplicated() {
+    let file = build(
+        RustToolchainToml::builder()
+            .with_channel("stable")
+            .with_components(["rustfmt", "clippy-preview", "rustfmt", "clippy"]),
+    )
+    .normalize();
+
+    let components = file.toolchain().spec().unwrap().components().unwrap();
+    let names = components.iter().map(|c| c.name()).collect::<Vec<_>>();
+    assert_eq!(names, ["clippy", "rustfmt"]);
+}
+
+#[test]
+fn targets_are_sorted_and_deduplicated() {
+    let file = build(
+        RustToolchainToml::builder()
+            .with_channel("stable")
+            .with_targets([
+                "wasm32-unknown-unknown",
+                "aarch64-apple-darwin",
+                "wasm32-unknown-unknown",
+            ]),
+    )
+    .normalize();
+
+    let targets = file.toolchain().spec().unwrap().targets().unwrap();
+    let names = targets.iter().map(|t| t.name()).collect::<Vec<_>>();
+    assert_eq!(names, ["aarch64-apple-darwin", "wasm32-unknown-unknown"]);
+}
+
+#[parameterized(
+    components = {
+        RustToolchainToml::builder().with_channel("stable").with_components(["rustfmt", "clippy", "rustfmt"]),
+        RustToolchainToml::builder().with_channel("stable").with_components(["clippy-preview", "rustfmt"]),
+    },
+    version = {
+        RustToolchainToml::builder().with_channel("1.70"),
+        RustToolchainToml::builder().with_channel("1.70.0"),
+    },
+    empty_array = {
+        RustToolchainToml::builder().with_channel("stable").with_targets(Vec::<String>::new()),
+        RustToolchainToml::builder().with_channel("stable"),
+    },
+    path = {
+        RustToolchainToml::builder().with_path("/opt/rust"),
+        RustToolchainToml::builder().with_path("/opt/rust"),
+    },
+)]
+fn equivalent(left: Builder, right: Builder) {
+    let (left, right) = (build(left), build(right));
+
+    assert!(left.is_equivalent(&right));
+    assert_eq!(left.normalize(), right.normalize());
+}
+
+#[parameterized(
+    channel = {
+        RustToolchainToml::builder().with_channel("stable"),
+        RustToolchainToml::builder().with_channel("beta"),
+    },
+    patch_version = {
+        RustToolchainToml::builder().with_channel("1.72"),
+        RustToolchainToml::builder().with_channel("1.72.1"),
+    },
+    components = {
+        RustToolchainToml::builder().with_channel("stable").with_components(["clippy"]),
+        RustToolchainToml::builder().with_channel("stable").with_components(["clippy", "rustfmt"]),
+    },
+    profile = {
+        RustToolchainToml::builder().with_channel("stable").with_profile("minimal"),
+        RustToolchainToml::builder().with_channel("stable"),
+    },
+)]
+fn not_equivalent(left: Builder, right: Builder) {
+    let (left, right) = (build(left), build(right));
+
+    assert!(!left.is_equivalent(&right));
+}
+
+#[test]
+fn is_idempotent() {
+    let file = build(
+        RustToolchainToml::builder()
+            .with_channel("1.70")
+            .with_components(["rustfmt-preview", "clippy"])
+            .with_targets(Vec::<String>::new()),
+    );
+
+    assert_eq!(file.normalize().normalize(), file.normalize());
+}
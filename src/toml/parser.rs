@@ -0,0 +1,345 @@
+use crate::options::{Encoding, Limits};
+use crate::toml::{
+    backend, Channel, Component, Key, Profile, RustToolchainToml, Target, ToolchainPath,
+    ToolchainSection, ToolchainSpec,
+};
+use crate::{ParserOptions, PortablePath, Warning};
+use std::collections::BTreeMap;
+use std::ops::Range;
+
+/// A parser for the TOML based toolchain file format.
+pub struct Parser<'content> {
+    pub(super) content: &'content [u8],
+
+    pub(super) options: ParserOptions,
+}
+
+impl<'content> Parser<'content> {
+    /// Initialize a parser for the `&str` content.
+    pub fn new(content: &'content str) -> Self {
+        Self {
+            content: content.as_bytes(),
+            options: ParserOptions::default(),
+        }
+    }
+
+    /// Initialize a parser.
+    pub fn from_slice(content: &'content [u8]) -> Self {
+        Self {
+            content,
+            options: ParserOptions::default(),
+        }
+    }
+
+    /// Replace the options used by this parser.
+    pub fn with_options(mut self, options: ParserOptions) -> Self {
+        self.options = options;
+        self
+    }
+}
+
+impl Parser<'_> {
+    pub fn parse(&self) -> Result<RustToolchainToml, ParserError> {
+        self.parse_with_warnings().map(|(file, _)| file)
+    }
+
+    /// Parse the content, and collect the warnings which were not promoted to errors.
+    pub fn parse_with_warnings(&self) -> Result<(RustToolchainToml, Vec<Warning>), ParserError> {
+        let mut warnings = Vec::new();
+
+        self.check_content()?;
+
+        self.options
+            .check_whitespace(self.content, &mut warnings)
+            .map_err(ParserError::Denied)?;
+
+        let raw: RawToolchainToml =
+            backend::from_slice(self.content).map_err(ParserError::TomlParse)?;
+
+        raw.toolchain.check_limits(&self.options.limits())?;
+
+        let toolchain = raw
+            .into_toolchain_section(&self.options, &mut warnings)
+            .map_err(ParserError::Denied)?;
+
+        let file = RustToolchainToml { toolchain };
+
+        self.options
+            .check_rustup_features(&file.rustup_features())
+            .map_err(ParserError::Denied)?;
+
+        Ok((file, warnings))
+    }
+}
+
+impl Parser<'_> {
+    /// Verify the encoding, and the limits which protect the deserializer, before deserializing.
+    pub(super) fn check_content(&self) -> Result<(), ParserError> {
+        if self.options.encoding() == Encoding::Ascii && !self.content.is_ascii() {
+            return Err(ParserError::InvalidEncodingStrict);
+        }
+
+        let limits = self.options.limits();
+
+        if self.content.len() > limits.max_bytes() {
+            return Err(ParserError::TooLarge(limits.max_bytes()));
+        }
+
+        if exceeds_nesting(self.content, limits.max_nesting()) {
+            return Err(ParserError::TooDeep(limits.max_nesting()));
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, thiserror::Error, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "kind", content = "value", rename_all = "snake_case")]
+pub enum ParserError {
+    #[error("Unable to parse toolchain file: {0}")]
+    TomlParse(TomlError),
+
+    #[error("Encountered invalid encoding while parsing TOML rust-toolchain file. The expected encoding to be US-ASCII, and lenient encoding was disabled.")]
+    InvalidEncodingStrict,
+
+    #[error("Rejected TOML toolchain file: {0}")]
+    Denied(Warning),
+
+    #[error("Unable to parse toolchain file: the content exceeds the limit of {0} bytes")]
+    TooLarge(usize),
+
+    #[error("Unable to parse toolchain file: the content exceeds the nesting limit of {0}")]
+    TooDeep(usize),
+
+    #[error("Unable to parse toolchain file: '{key}' exceeds the limit of {limit} items")]
+    TooManyItems { key: Key, limit: usize },
+
+    #[error("Unable to parse toolchain file: a value of '{key}' exceeds the length limit of {limit} bytes")]
+    StringTooLong { key: Key, limit: usize },
+}
+
+/// An error produced by the TOML library, independent of which library is used.
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error, serde::Serialize, serde::Deserialize)]
+#[error("{message}")]
+pub struct TomlError {
+    pub(super) message: String,
+    pub(super) span: Option<Range<usize>>,
+}
+
+impl TomlError {
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// The byte range of the content at which the error occurred, if known.
+    pub fn span(&self) -> Option<Range<usize>> {
+        self.span.clone()
+    }
+}
+
+/// The unvalidated layout of a TOML toolchain file, as accepted by rustup.
+#[derive(serde::Deserialize)]
+struct RawToolchainToml {
+    toolchain: RawToolchainSection,
+    #[serde(flatten)]
+    unknown: BTreeMap<String, serde::de::IgnoredAny>,
+}
+
+#[derive(serde::Deserialize)]
+struct RawToolchainSection {
+    path: Option<PortablePath>,
+    channel: Option<Channel>,
+    components: Option<Vec<Component>>,
+    targets: Option<Vec<Target>>,
+    profile: Option<Profile>,
+    #[serde(flatten)]
+    unknown: BTreeMap<String, serde::de::IgnoredAny>,
+}
+
+impl RawToolchainSection {
+    fn check_limits(&self, limits: &Limits) -> Result<(), ParserError> {
+        let components = self.components.as_deref().unwrap_or_default();
+        let targets = self.targets.as_deref().unwrap_or_default();
+
+        for (key, len) in [
+            (Key::Components, components.len()),
+            (Key::Targets, targets.len()),
+        ] {
+            if len > limits.max_items() {
+                return Err(ParserError::TooManyItems {
+                    key,
+                    limit: limits.max_items(),
+                });
+            }
+        }
+
+        let mut values = [
+            (Key::Path, self.path.as_ref().map(|path| path.as_str())),
+            (Key::Channel, self.channel.as_ref().map(Channel::name)),
+            (Key::Profile, self.profile.as_ref().map(Profile::name)),
+        ]
+        .into_iter()
+        .filter_map(|(key, value)| Some((key, value?)))
+        .chain(components.iter().map(|c| (Key::Components, c.name())))
+        .chain(targets.iter().map(|t| (Key::Targets, t.name())));
+
+        match values.find(|(_, value)| value.len() > limits.max_string_len()) {
+            Some((key, _)) => Err(ParserError::StringTooLong {
+                key,
+                limit: limits.max_string_len(),
+            }),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Whether arrays, inline tables and tables are nested deeper than the given maximum.
+///
+/// Every segment of a table header or dotted key counts as a level, since it defines a table. The
+/// levels of a key are added to those of the header of its table.
+fn exceeds_nesting(content: &[u8], max: usize) -> bool {
+    // The depth of the table of the last header.
+    let mut table = 0usize;
+    // The closing delimiter of each open array and inline table, with the depth within it.
+    let mut open: Vec<(u8, usize)> = Vec::new();
+    let mut depth = 0usize;
+    let mut in_key = true;
+    let mut in_header = false;
+    let mut index = 0;
+
+    while index < content.len() {
+        match content[index] {
+            b'#' => {
+                while index < content.len() && content[index] != b'\n' {
+                    index += 1;
+                }
+                continue;
+            }
+            quote @ (b'"' | b'\'') if content[index..].starts_with(&[quote; 3]) => {
+                index += 3;
+                while index < content.len() && !content[index..].starts_with(&[quote; 3]) {
+                    index += if quote == b'"' && content[index] == b'\\' {
+                        2
+                    } else {
+                        1
+                    };
+                }
+                index += 2;
+            }
+            quote @ (b'"' | b'\'') => {
+                index += 1;
+                while index < content.len() && content[index] != quote && content[index] != b'\n' {
+                    index += if quote == b'"' && content[index] == b'\\' {
+                        2
+                    } else {
+                        1
+                    };
+                }
+            }
+            b'\n' if open.is_empty() => {
+                depth = table;
+                in_key = true;
+                in_header = false;
+            }
+            b'[' if in_key && !in_header && open.is_empty() => {
+                // A table header, or an array of tables header, of which the first segment is
+                // the first level.
+                if content.get(index + 1) == Some(&b'[') {
+                    index += 1;
+                }
+
+                depth = 1;
+                in_header = true;
+            }
+            b']' if in_header => {
+                table = depth;
+                in_key = false;
+                in_header = false;
+            }
+            b'.' if in_key || in_header => depth += 1,
+            b'=' if in_key => in_key = false,
+            delimiter @ (b'[' | b'{') => {
+                depth += 1;
+                open.push((if delimiter == b'[' { b']' } else { b'}' }, depth));
+                in_key = delimiter == b'{';
+            }
+            b',' => {
+                if let Some(&(closing, within)) = open.last() {
+                    depth = within;
+                    in_key = closing == b'}';
+                }
+            }
+            b']' | b'}' => {
+                if let Some((_, within)) = open.pop() {
+                    depth = within - 1;
+                }
+
+                in_key = false;
+            }
+            _ => {}
+        }
+
+        if depth > max {
+            return true;
+        }
+
+        index += 1;
+    }
+
+    false
+}
+
+impl RawToolchainToml {
+    fn into_toolchain_section(
+        self,
+        options: &ParserOptions,
+        warnings: &mut Vec<Warning>,
+    ) -> Result<ToolchainSection, Warning> {
+        let section = self.toolchain;
+
+        let unknown_keys = self.unknown.into_keys().chain(
+            section
+                .unknown
+                .into_keys()
+                .map(|key| format!("toolchain.{key}")),
+        );
+
+        for key in unknown_keys {
+            options.report(options.unknown_keys(), Warning::UnknownKey(key), warnings)?;
+        }
+
+        match section.path {
+            Some(path) => {
+                if section.channel.is_some()
+                    || section.components.is_some()
+                    || section.targets.is_some()
+                    || section.profile.is_some()
+                {
+                    options.report(
+                        options.path_with_spec_keys(),
+                        Warning::PathWithSpecKeys,
+                        warnings,
+                    )?;
+                }
+
+                if path.is_relative() {
+                    options.report(
+                        options.relative_paths(),
+                        Warning::RelativePath(path.to_string()),
+                        warnings,
+                    )?;
+                }
+
+                options.check_platform(&path, warnings)?;
+
+                Ok(ToolchainSection::Path(ToolchainPath { path }))
+            }
+            None => Ok(ToolchainSection::Spec(ToolchainSpec {
+                channel: section.channel,
+                components: section.components,
+                targets: section.targets,
+                profile: section.profile,
+            })),
+        }
+    }
+}
@@ -0,0 +1,386 @@
+use crate::toml::{
+    Channel, Component, Parser, ParserError, Profile, Target, ToolchainPath, ToolchainSection,
+    ToolchainSpec,
+};
+use std::ops::Range;
+
+#[cfg(test)]
+mod tests;
+
+impl Parser<'_> {
+    /// Parse the content, and recover from errors as well as possible.
+    ///
+    /// Intended for content which is still being edited: instead of failing on the first error, the
+    /// keys of the `[toolchain]` table which can be read are collected into a best-effort
+    /// [`ToolchainSection`], and every problem is reported as a [`Diagnostic`].
+    pub fn parse_recovering(&self) -> Recovered {
+        let error = match self.parse_with_warnings() {
+            Ok((file, warnings)) => {
+                return Recovered {
+                    toolchain: Some(file.toolchain),
+                    diagnostics: warnings
+                        .into_iter()
+                        .map(|warning| {
+                            Diagnostic::new(Severity::Warning, warning.to_string(), None)
+                        })
+                        .collect(),
+                }
+            }
+            Err(error) => error,
+        };
+
+        let span = match error {
+            ParserError::TomlParse(ref inner) => inner.span(),
+            _ => None,
+        };
+
+        let mut diagnostics = vec![Diagnostic::new(Severity::Error, error.to_string(), span)];
+
+        // Content which exceeds the size or nesting limits is not scanned either.
+        if let ParserError::TooLarge(_) | ParserError::TooDeep(_) = error {
+            return Recovered {
+                toolchain: None,
+                diagnostics,
+            };
+        }
+
+        let content = String::from_utf8_lossy(self.content);
+        let toolchain = recover(&content, &mut diagnostics);
+
+        Recovered {
+            toolchain,
+            diagnostics,
+        }
+    }
+}
+
+/// The best-effort result of [`Parser::parse_recovering`].
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Recovered {
+    toolchain: Option<ToolchainSection>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Recovered {
+    /// The toolchain section, as far as it could be recovered, or `None` if no toolchain table was found.
+    pub fn toolchain(&self) -> Option<&ToolchainSection> {
+        self.toolchain.as_ref()
+    }
+
+    /// The toolchain specification, as far as it could be recovered.
+    pub fn spec(&self) -> Option<&ToolchainSpec> {
+        self.toolchain.as_ref().and_then(ToolchainSection::spec)
+    }
+
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+
+    /// Whether the content was parsed without errors.
+    pub fn is_complete(&self) -> bool {
+        self.diagnostics
+            .iter()
+            .all(|diagnostic| diagnostic.severity != Severity::Error)
+    }
+}
+
+/// A problem found while parsing, optionally located by a byte range within the content.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Diagnostic {
+    severity: Severity,
+    message: String,
+    span: Option<Range<usize>>,
+}
+
+impl Diagnostic {
+    fn new(severity: Severity, message: impl Into<String>, span: Option<Range<usize>>) -> Self {
+        Self {
+            severity,
+            message: message.into(),
+            span,
+        }
+    }
+
+    pub fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn span(&self) -> Option<Range<usize>> {
+        self.span.clone()
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+#[derive(Default)]
+struct Fields {
+    found: bool,
+    path: Option<String>,
+    channel: Option<String>,
+    components: Option<Vec<String>>,
+    targets: Option<Vec<String>>,
+    profile: Option<String>,
+}
+
+/// Scan the content line by line, and collect the values of the toolchain keys which are readable.
+fn recover(content: &str, diagnostics: &mut Vec<Diagnostic>) -> Option<ToolchainSection> {
+    let mut lines = Vec::new();
+    let mut offset = 0;
+
+    for line in content.split_inclusive('\n') {
+        lines.push((offset, line));
+        offset += line.len();
+    }
+
+    let mut fields = Fields::default();
+    let mut table = String::new();
+    let mut index = 0;
+
+    while index < lines.len() {
+        let (start, line) = lines[index];
+        index += 1;
+
+        let trimmed = line.trim();
+
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        if let Some(header) = trimmed.strip_prefix('[') {
+            table = header
+                .split(']')
+                .next()
+                .unwrap_or_default()
+                .trim()
+                .to_string();
+            fields.found |= table == "toolchain";
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once('=') else {
+            diagnostics.push(Diagnostic::new(
+                Severity::Error,
+                "Expected a key-value pair",
+                Some(start..start + line.trim_end().len()),
+            ));
+            continue;
+        };
+
+        let key = key.trim().trim_matches('"');
+        let key = match table.as_str() {
+            "" => key.to_string(),
+            table => format!("{table}.{key}"),
+        };
+
+        let Some(field) = key.strip_prefix("toolchain.") else {
+            continue;
+        };
+
+        fields.found = true;
+
+        // Arrays may span multiple lines, until they are closed, or until the next key or table.
+        let mut value = value.to_string();
+        let mut end = start + line.len();
+
+        if value.trim_start().starts_with('[') {
+            while !scan(&value).closed && index < lines.len() {
+                let next = lines[index].1.trim();
+
+                if next.starts_with('[') || next.contains('=') {
+                    break;
+                }
+
+                value.push_str(lines[index].1);
+                end += lines[index].1.len();
+                index += 1;
+            }
+        }
+
+        let span = start..start + content[start..end].trim_end().len();
+
+        match field {
+            "path" => fields.path = recover_string(field, &value, span, diagnostics),
+            "channel" => fields.channel = recover_string(field, &value, span, diagnostics),
+            "profile" => fields.profile = recover_string(field, &value, span, diagnostics),
+            "components" => {
+                fields.components = Some(recover_array(field, &value, span, diagnostics))
+            }
+            "targets" => fields.targets = Some(recover_array(field, &value, span, diagnostics)),
+            _ => {}
+        }
+    }
+
+    if !fields.found {
+        return None;
+    }
+
+    Some(match fields.path {
+        Some(path) => ToolchainSection::Path(ToolchainPath { path: path.into() }),
+        None => ToolchainSection::Spec(ToolchainSpec {
+            channel: fields.channel.map(Channel),
+            components: fields
+                .components
+                .map(|items| items.into_iter().map(Component).collect()),
+            targets: fields
+                .targets
+                .map(|items| items.into_iter().map(Target).collect()),
+            profile: fields.profile.map(Profile),
+        }),
+    })
+}
+
+fn recover_string(
+    key: &str,
+    value: &str,
+    span: Range<usize>,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Option<String> {
+    let scanned = scan(value);
+
+    if !scanned.terminated {
+        diagnostics.push(Diagnostic::new(
+            Severity::Error,
+            format!("Unterminated string for '{key}'"),
+            Some(span),
+        ));
+        return None;
+    }
+
+    match scanned.strings.as_slice() {
+        [string] if !scanned.array && scanned.rest_is_empty => Some(string.clone()),
+        [string, ..] => {
+            diagnostics.push(Diagnostic::new(
+                Severity::Error,
+                format!("Expected a single string for '{key}'"),
+                Some(span),
+            ));
+            Some(string.clone())
+        }
+        [] => {
+            diagnostics.push(Diagnostic::new(
+                Severity::Error,
+                format!("Expected a string for '{key}'"),
+                Some(span),
+            ));
+            None
+        }
+    }
+}
+
+fn recover_array(
+    key: &str,
+    value: &str,
+    span: Range<usize>,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Vec<String> {
+    let scanned = scan(value);
+
+    if !scanned.array {
+        diagnostics.push(Diagnostic::new(
+            Severity::Error,
+            format!("Expected an array of strings for '{key}'"),
+            Some(span),
+        ));
+    } else if !scanned.terminated || !scanned.closed {
+        diagnostics.push(Diagnostic::new(
+            Severity::Error,
+            format!("Unterminated array for '{key}'"),
+            Some(span),
+        ));
+    }
+
+    scanned.strings
+}
+
+/// The strings found within a TOML value, and whether the value is well-formed.
+#[derive(Default)]
+struct Scanned {
+    strings: Vec<String>,
+    /// Whether the value is an array.
+    array: bool,
+    /// Whether the array was closed.
+    closed: bool,
+    /// Whether every string was terminated.
+    terminated: bool,
+    /// Whether nothing but whitespace and comments follows the value.
+    rest_is_empty: bool,
+}
+
+fn scan(value: &str) -> Scanned {
+    let mut scanned = Scanned {
+        terminated: true,
+        rest_is_empty: true,
+        ..Scanned::default()
+    };
+
+    let value = value.trim_start();
+    scanned.array = value.starts_with('[');
+
+    let mut chars = value.chars();
+    let mut depth = 0usize;
+    let mut done = false;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '#' => {
+                // Skip the comment, up to the end of the line.
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        break;
+                    }
+                }
+            }
+            _ if c.is_whitespace() || c == ',' => {}
+            '[' if !done => depth += 1,
+            ']' if !done && depth > 0 => {
+                depth -= 1;
+                if depth == 0 {
+                    scanned.closed = true;
+                    done = true;
+                }
+            }
+            '"' | '\'' if !done => {
+                let mut string = String::new();
+                let mut terminated = false;
+
+                while let Some(s) = chars.next() {
+                    match s {
+                        _ if s == c => {
+                            terminated = true;
+                            break;
+                        }
+                        '\n' => break,
+                        '\\' if c == '"' => match chars.next() {
+                            Some('n') => string.push('\n'),
+                            Some('t') => string.push('\t'),
+                            Some('r') => string.push('\r'),
+                            Some(escaped) => string.push(escaped),
+                            None => break,
+                        },
+                        _ => string.push(s),
+                    }
+                }
+
+                if !terminated {
+                    scanned.terminated = false;
+                    return scanned;
+                }
+
+                scanned.strings.push(string);
+                done |= !scanned.array;
+            }
+            _ => scanned.rest_is_empty = false,
+        }
+    }
+
+    scanned
+}
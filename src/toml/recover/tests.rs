@@ -0,0 +1,146 @@
+use crate::toml::{Parser, Severity, ToolchainSection};
+use crate::ParserOptions;
+use camino::Utf8Path;
+
+fn names<T>(items: Option<&[T]>, name: fn(&T) -> &str) -> Vec<&str> {
+    items.unwrap_or_default().iter().map(name).collect()
+}
+
+#[test]
+fn complete_file() {
+    let content = "[toolchain]\nchannel = \"nightly\"\ncomponents = [\"rustfmt\"]\n";
+    let recovered = Parser::new(content).parse_recovering();
+
+    assert!(recovered.is_complete());
+    assert!(recovered.diagnostics().is_empty());
+
+    let spec = recovered.spec().unwrap();
+    assert_eq!(spec.channel().unwrap().name(), "nightly");
+    assert_eq!(names(spec.components(), |c| c.name()), vec!["rustfmt"]);
+}
+
+#[test]
+fn complete_file_with_warnings() {
+    let content = "[toolchain]\nchannel = \"nightly\"\n\n\n";
+    let options = ParserOptions::pedantic().with_warnings_as_errors(false);
+    let recovered = Parser::new(content)
+        .with_options(options)
+        .parse_recovering();
+
+    assert!(recovered.is_complete());
+    assert_eq!(recovered.diagnostics().len(), 1);
+    assert_eq!(recovered.diagnostics()[0].severity(), Severity::Warning);
+}
+
+#[test]
+fn half_typed_components() {
+    let content = "[toolchain]\nchannel = \"nightly\"\ncomponents = [\"rustfmt\", ";
+    let recovered = Parser::new(content).parse_recovering();
+
+    assert!(!recovered.is_complete());
+
+    let spec = recovered.spec().unwrap();
+    assert_eq!(spec.channel().unwrap().name(), "nightly");
+    assert_eq!(names(spec.components(), |c| c.name()), vec!["rustfmt"]);
+
+    let diagnostics = recovered.diagnostics();
+    assert_eq!(diagnostics.len(), 2);
+    assert!(diagnostics[0].span().is_some());
+    assert_eq!(
+        diagnostics[1].message(),
+        "Unterminated array for 'components'"
+    );
+    assert_eq!(diagnostics[1].span(), Some(32..content.trim_end().len()));
+}
+
+#[test]
+fn half_typed_string_in_array() {
+    let content = "[toolchain]\ntargets = [\"wasm32-unknown-unknown\", \"x86_64";
+    let recovered = Parser::new(content).parse_recovering();
+
+    let spec = recovered.spec().unwrap();
+    assert_eq!(
+        names(spec.targets(), |t| t.name()),
+        vec!["wasm32-unknown-unknown"]
+    );
+}
+
+#[test]
+fn multi_line_array_followed_by_key() {
+    let content =
+        "[toolchain]\ncomponents = [\n  \"rustfmt\",\n  \"clippy\",\nprofile = \"minimal\"\n";
+    let recovered = Parser::new(content).parse_recovering();
+
+    let spec = recovered.spec().unwrap();
+    assert_eq!(
+        names(spec.components(), |c| c.name()),
+        vec!["rustfmt", "clippy"]
+    );
+    assert_eq!(spec.profile().unwrap().name(), "minimal");
+}
+
+#[test]
+fn half_typed_channel() {
+    let content = "[toolchain]\nchannel = \"nigh\ncomponents = [\"rustfmt\"]";
+    let recovered = Parser::new(content).parse_recovering();
+
+    let spec = recovered.spec().unwrap();
+    assert!(spec.channel().is_none());
+    assert_eq!(names(spec.components(), |c| c.name()), vec!["rustfmt"]);
+    assert!(recovered
+        .diagnostics()
+        .iter()
+        .any(|d| d.message() == "Unterminated string for 'channel'"));
+}
+
+#[test]
+fn unquoted_channel() {
+    let content = "[toolchain]\nchannel = nightly\n";
+    let recovered = Parser::new(content).parse_recovering();
+
+    assert!(recovered.spec().unwrap().channel().is_none());
+    assert!(recovered
+        .diagnostics()
+        .iter()
+        .any(|d| d.message() == "Expected a string for 'channel'"));
+}
+
+#[test]
+fn dotted_keys() {
+    let content = "toolchain.channel = \"stable\"\ntoolchain.components = [";
+    let recovered = Parser::new(content).parse_recovering();
+
+    let spec = recovered.spec().unwrap();
+    assert_eq!(spec.channel().unwrap().name(), "stable");
+    assert_eq!(spec.components(), Some([].as_slice()));
+}
+
+#[test]
+fn keys_of_other_tables_are_ignored() {
+    let content = "[other]\nchannel = \"beta\"\n[toolchain]\nchannel = \"stable\"\nprofile = ";
+    let recovered = Parser::new(content).parse_recovering();
+
+    let spec = recovered.spec().unwrap();
+    assert_eq!(spec.channel().unwrap().name(), "stable");
+    assert!(spec.profile().is_none());
+}
+
+#[test]
+fn path() {
+    let content = "[toolchain]\npath = \"/my/toolchain\"\nchannel =";
+    let recovered = Parser::new(content).parse_recovering();
+
+    let Some(ToolchainSection::Path(path)) = recovered.toolchain() else {
+        panic!("expected a path");
+    };
+    assert_eq!(path.path(), Utf8Path::new("/my/toolchain"));
+}
+
+#[test]
+fn no_toolchain_table() {
+    let content = "[toolch";
+    let recovered = Parser::new(content).parse_recovering();
+
+    assert!(recovered.toolchain().is_none());
+    assert!(!recovered.is_complete());
+}
@@ -0,0 +1,120 @@
+//! Ordering of toolchain channels which name a specific release.
+
+use core::cmp::Ordering;
+
+/// The order of two releases, if they can be compared: both must be versions like `1.70.0`, or
+/// dated releases of the same channel, like `nightly-2023-06-01`, for the same host. A version
+/// without a patch version, like `1.70`, is newer than every patch release of it.
+pub(crate) fn order(left: &str, right: &str) -> Option<Ordering> {
+    match (Release::parse(left)?, Release::parse(right)?) {
+        (Release::Version(l, l_host), Release::Version(r, r_host)) if l_host == r_host => {
+            Some(l.cmp(&r))
+        }
+        (Release::Dated(l_name, l_date, l_host), Release::Dated(r_name, r_date, r_host))
+            if l_name == r_name && l_host == r_host =>
+        {
+            Some(l_date.cmp(r_date))
+        }
+        _ => None,
+    }
+}
+
+/// The number of minor releases between two versions with the same major version, like 2 between
+/// `1.70.0` and `1.72.1`.
+pub(crate) fn minor_releases_between(left: &str, right: &str) -> Option<u64> {
+    match (Release::parse(left)?, Release::parse(right)?) {
+        (Release::Version(l, _), Release::Version(r, _)) if l.major == r.major => {
+            Some(l.minor.abs_diff(r.minor))
+        }
+        _ => None,
+    }
+}
+
+/// The version of a version channel, like `1.70.0` for `1.70.0-x86_64-unknown-linux-gnu`.
+pub(crate) fn version(name: &str) -> Option<Version> {
+    match Release::parse(name)? {
+        Release::Version(version, _) => Some(version),
+        Release::Dated(..) => None,
+    }
+}
+
+/// The date of a dated release of the given channel, like `2023-06-01` for `nightly-2023-06-01`.
+pub(crate) fn date<'a>(name: &'a str, channel: &str) -> Option<&'a str> {
+    match Release::parse(name)? {
+        Release::Dated(name, date, _) if name == channel => Some(date),
+        _ => None,
+    }
+}
+
+/// A version, ordered by its major, minor and patch version.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct Version {
+    major: u64,
+    minor: u64,
+    patch: Patch,
+}
+
+/// The patch version of a [`Version`], where an omitted patch version selects the latest patch
+/// release, and thus orders after every patch release.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Patch {
+    Release(u64),
+    Latest,
+}
+
+/// A release which can be ordered, with the host which may follow it.
+enum Release<'a> {
+    Version(Version, Option<&'a str>),
+    Dated(&'a str, &'a str, Option<&'a str>),
+}
+
+impl<'a> Release<'a> {
+    fn parse(name: &'a str) -> Option<Self> {
+        let (channel, rest) = match name.split_once('-') {
+            Some((channel, rest)) => (channel, Some(rest)),
+            None => (name, None),
+        };
+
+        let mut parts = channel.split('.').map(|part| part.parse::<u64>().ok());
+
+        if let (Some(Some(major)), Some(Some(minor)), patch, None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        {
+            let patch = match patch {
+                Some(Some(patch)) => Patch::Release(patch),
+                Some(None) => return None,
+                None => Patch::Latest,
+            };
+
+            return Some(Self::Version(
+                Version {
+                    major,
+                    minor,
+                    patch,
+                },
+                rest,
+            ));
+        }
+
+        if !matches!(channel, "stable" | "beta" | "nightly") {
+            return None;
+        }
+
+        // A date, like `2023-06-01`, optionally followed by a host.
+        let rest = rest?;
+        let date = rest.get(..10)?;
+        let is_date = date.bytes().enumerate().all(|(index, b)| match index {
+            4 | 7 => b == b'-',
+            _ => b.is_ascii_digit(),
+        });
+
+        if !is_date {
+            return None;
+        }
+
+        match rest.get(10..)? {
+            "" => Some(Self::Dated(channel, date, None)),
+            host => Some(Self::Dated(channel, date, Some(host.strip_prefix('-')?))),
+        }
+    }
+}
@@ -0,0 +1,98 @@
+//! Writing the TOML toolchain file model back to text.
+//!
+//! The output is produced without a TOML library, so it is the same regardless of the selected
+//! backend, and is also available without the standard library.
+
+use crate::toml::{RustToolchainToml, ToolchainSection};
+use alloc::string::String;
+use core::fmt::Write;
+
+#[cfg(all(test, any(feature = "use_toml_edit", feature = "use_toml")))]
+mod tests;
+
+impl RustToolchainToml {
+    /// Write the toolchain file in the TOML format.
+    ///
+    /// The output is deterministic: the `[toolchain]` table holds the keys in the order `path`,
+    /// `channel`, `components`, `targets` and `profile`, where absent keys are left out, and arrays
+    /// keep their order. Parsing the output with the default options results in an equal value.
+    pub fn to_toml_string(&self) -> String {
+        let mut out = String::from("[toolchain]\n");
+
+        match self.toolchain() {
+            ToolchainSection::Path(path) => {
+                write_key(&mut out, "path");
+                write_string(&mut out, path.portable_path().as_str());
+                out.push('\n');
+            }
+            ToolchainSection::Spec(spec) => {
+                if let Some(channel) = spec.channel() {
+                    write_key(&mut out, "channel");
+                    write_string(&mut out, channel.name());
+                    out.push('\n');
+                }
+
+                if let Some(components) = spec.components() {
+                    write_key(&mut out, "components");
+                    write_array(&mut out, components.iter().map(|c| c.name()));
+                }
+
+                if let Some(targets) = spec.targets() {
+                    write_key(&mut out, "targets");
+                    write_array(&mut out, targets.iter().map(|t| t.name()));
+                }
+
+                if let Some(profile) = spec.profile() {
+                    write_key(&mut out, "profile");
+                    write_string(&mut out, profile.name());
+                    out.push('\n');
+                }
+            }
+        }
+
+        out
+    }
+}
+
+fn write_key(out: &mut String, key: &str) {
+    out.push_str(key);
+    out.push_str(" = ");
+}
+
+fn write_array<'a>(out: &mut String, values: impl Iterator<Item = &'a str>) {
+    out.push('[');
+
+    for (index, value) in values.enumerate() {
+        if index > 0 {
+            out.push_str(", ");
+        }
+
+        write_string(out, value);
+    }
+
+    out.push_str("]\n");
+}
+
+/// Write the value as a TOML basic string, escaping quotes, backslashes and control characters.
+fn write_string(out: &mut String, value: &str) {
+    out.push('"');
+
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\u{8}' => out.push_str("\\b"),
+            '\t' => out.push_str("\\t"),
+            '\n' => out.push_str("\\n"),
+            '\u{c}' => out.push_str("\\f"),
+            '\r' => out.push_str("\\r"),
+            c if c < ' ' || c == '\u{7f}' => {
+                // Writing to a `String` can not fail.
+                let _ = write!(out, "\\u{:04X}", u32::from(c));
+            }
+            c => out.push(c),
+        }
+    }
+
+    out.push('"');
+}
@@ -0,0 +1,125 @@
+use crate::toml::{
+    Channel, Component, Parser, Profile, RustToolchainToml, Target, ToolchainPath,
+    ToolchainSection, ToolchainSpec,
+};
+use crate::PortablePath;
+use proptest::prelude::*;
+use yare::parameterized;
+
+const RUSTUP_BOOK_SPEC: &str =
+    include_str!("../../../tests/fixtures/rustup-book-layout/rust-toolchain.toml");
+
+#[parameterized(
+    spec = {
+        RustToolchainToml::builder()
+            .with_channel("nightly-2020-07-10")
+            .with_components(["rustfmt", "rustc-dev"])
+            .with_targets(["wasm32-unknown-unknown", "thumbv2-none-eabi"])
+            .with_profile("minimal"),
+        "[toolchain]\nchannel = \"nightly-2020-07-10\"\ncomponents = [\"rustfmt\", \"rustc-dev\"]\ntargets = [\"wasm32-unknown-unknown\", \"thumbv2-none-eabi\"]\nprofile = \"minimal\"\n",
+    },
+    channel_only = {
+        RustToolchainToml::builder().with_channel("stable"),
+        "[toolchain]\nchannel = \"stable\"\n",
+    },
+    empty_components = {
+        RustToolchainToml::builder().with_channel("stable").with_components(Vec::<String>::new()),
+        "[toolchain]\nchannel = \"stable\"\ncomponents = []\n",
+    },
+    path = {
+        RustToolchainToml::builder().with_path("/opt/rust"),
+        "[toolchain]\npath = \"/opt/rust\"\n",
+    },
+    windows_path = {
+        RustToolchainToml::builder().with_path(r"C:\toolchains\rust"),
+        "[toolchain]\npath = \"C:\\\\toolchains\\\\rust\"\n",
+    },
+)]
+fn to_toml_string(builder: crate::toml::Builder, expected: &str) {
+    let file = builder.build().unwrap();
+
+    assert_eq!(file.to_toml_string(), expected);
+}
+
+#[test]
+fn escapes_quotes_and_control_characters() {
+    let file = RustToolchainToml {
+        toolchain: ToolchainSection::Spec(ToolchainSpec {
+            channel: Some(Channel("a\"b\\c\td\u{1}e\u{7f}".to_string())),
+            components: None,
+            targets: None,
+            profile: None,
+        }),
+    };
+
+    assert_eq!(
+        file.to_toml_string(),
+        "[toolchain]\nchannel = \"a\\\"b\\\\c\\td\\u0001e\\u007F\"\n"
+    );
+}
+
+#[test]
+fn empty_spec() {
+    let file = RustToolchainToml {
+        toolchain: ToolchainSection::Spec(ToolchainSpec {
+            channel: None,
+            components: None,
+            targets: None,
+            profile: None,
+        }),
+    };
+
+    assert_eq!(file.to_toml_string(), "[toolchain]\n");
+    assert_eq!(Parser::new(&file.to_toml_string()).parse().unwrap(), file);
+}
+
+#[test]
+fn rustup_book_layout() {
+    let file = Parser::new(RUSTUP_BOOK_SPEC).parse().unwrap();
+
+    assert_eq!(Parser::new(&file.to_toml_string()).parse().unwrap(), file);
+}
+
+fn toolchain_section() -> impl Strategy<Value = ToolchainSection> {
+    let names = || proptest::collection::vec(any::<String>(), 0..4);
+
+    let path = any::<String>().prop_map(|path| {
+        ToolchainSection::Path(ToolchainPath {
+            path: PortablePath::new(path),
+        })
+    });
+
+    let spec = (
+        proptest::option::of(any::<String>()),
+        proptest::option::of(names()),
+        proptest::option::of(names()),
+        proptest::option::of(any::<String>()),
+    )
+        .prop_map(|(channel, components, targets, profile)| {
+            ToolchainSection::Spec(ToolchainSpec {
+                channel: channel.map(Channel),
+                components: components.map(|names| names.into_iter().map(Component).collect()),
+                targets: targets.map(|names| names.into_iter().map(Target).collect()),
+                profile: profile.map(Profile),
+            })
+        });
+
+    prop_oneof![path, spec]
+}
+
+proptest! {
+    #[test]
+    fn reparses_to_equal_value(toolchain in toolchain_section()) {
+        let file = RustToolchainToml { toolchain };
+
+        prop_assert_eq!(Parser::new(&file.to_toml_string()).parse().unwrap(), file);
+    }
+
+    #[test]
+    fn is_deterministic(toolchain in toolchain_section()) {
+        let file = RustToolchainToml { toolchain };
+        let reparsed = Parser::new(&file.to_toml_string()).parse().unwrap();
+
+        prop_assert_eq!(reparsed.to_toml_string(), file.to_toml_string());
+    }
+}
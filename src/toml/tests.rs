@@ -1,4 +1,4 @@
-use crate::toml::{Channel, Parser, ParserError, ToolchainSpec};
+use crate::toml::{backend, Channel, Parser, ParserError, ToolchainSpec};
 use crate::RustToolchainToml;
 use camino::Utf8Path;
 
@@ -13,7 +13,7 @@ mod complete_file {
 
     #[test]
     fn parse_rustup_book_layout() {
-        let rust_toolchain: RustToolchainToml = toml_edit::de::from_str(RUSTUP_BOOK_SPEC).unwrap();
+        let rust_toolchain: RustToolchainToml = backend::from_str(RUSTUP_BOOK_SPEC).unwrap();
 
         let toolchain = rust_toolchain.toolchain();
         assert!(toolchain.path().is_none());
@@ -103,7 +103,7 @@ mod complete_file {
     #[test]
     fn parse_rustup_book_local_toolchain() {
         let rust_toolchain: RustToolchainToml =
-            toml_edit::de::from_str(RUSTUP_BOOK_LOCAL_TOOLCHAIN).unwrap();
+            backend::from_str(RUSTUP_BOOK_LOCAL_TOOLCHAIN).unwrap();
 
         let toolchain = rust_toolchain.toolchain();
         let path = toolchain.path();
@@ -130,6 +130,21 @@ mod complete_file {
 
         assert_eq!(path, Utf8Path::new("/path/to/local/toolchain"));
     }
+
+    #[cfg(feature = "use_toml_edit")]
+    #[yare::parameterized(
+        spec = { RUSTUP_BOOK_SPEC },
+        local_toolchain = { RUSTUP_BOOK_LOCAL_TOOLCHAIN },
+    )]
+    fn serialize_as_toml(content: &str) {
+        let rust_toolchain = Parser::new(content).parse().unwrap();
+        let serialized = toml_edit::ser::to_string(&rust_toolchain).unwrap();
+
+        assert_eq!(
+            backend::from_str::<RustToolchainToml>(&serialized).unwrap(),
+            rust_toolchain
+        );
+    }
 }
 
 mod from_slice {
@@ -297,17 +312,116 @@ mod getters {
 }
 
 mod toolchain_path {
+    use crate::path::ResolveError;
     use crate::toml::ToolchainPath;
-    use camino::Utf8Path;
+    use crate::{PortablePath, Warning};
+    use camino::{Utf8Path, Utf8PathBuf};
+    use yare::parameterized;
 
     #[test]
     fn path() {
         let value = ToolchainPath {
-            path: Utf8Path::new("/my/path").to_path_buf(),
+            path: PortablePath::from("/my/path"),
         };
 
         assert_eq!(value.path(), Utf8Path::new("/my/path"));
-        assert_eq!(value.path(), &value.path)
+        assert_eq!(value.portable_path(), &value.path)
+    }
+
+    #[parameterized(
+        sibling = { "toolchain", "/repo/toolchain" },
+        nested = { "./toolchains/../toolchains/stable", "/repo/toolchains/stable" },
+        absolute = { "/opt/rust/../rust", "/opt/rust" },
+        windows_separators = { r"toolchains\..\toolchains\stable", "/repo/toolchains/stable" },
+        windows_absolute = { r"C:\toolchains\..\rust", "C:/rust" },
+    )]
+    fn resolve(path: &str, expected: &str) {
+        let value = ToolchainPath {
+            path: PortablePath::from(path),
+        };
+
+        let resolved = value.resolve("/repo/rust-toolchain.toml").unwrap();
+        assert_eq!(resolved.path(), Utf8Path::new(expected));
+        assert!(resolved.warnings().is_empty());
+    }
+
+    #[test]
+    fn resolve_outside_root() {
+        let value = ToolchainPath {
+            path: PortablePath::from("../../toolchain"),
+        };
+
+        let resolved = value.resolve("/repo/rust-toolchain.toml").unwrap();
+        assert_eq!(resolved.path(), Utf8Path::new("/toolchain"));
+        assert_eq!(
+            resolved.warnings(),
+            &[Warning::PathOutsideRoot("../../toolchain".to_string())]
+        );
+    }
+
+    #[parameterized(
+        inside = { "../toolchain", "/repo/toolchain", false },
+        outside = { "../../toolchain", "/toolchain", true },
+        windows_outside = { r"..\..\toolchain", "/toolchain", true },
+        sibling_of_root = { "../../repository", "/repository", true },
+    )]
+    fn resolve_within(path: &str, expected: &str, outside: bool) {
+        let value = ToolchainPath {
+            path: PortablePath::from(path),
+        };
+
+        let resolved = value
+            .resolve_within("/repo/crate/rust-toolchain.toml", "/repo")
+            .unwrap();
+        assert_eq!(resolved.path(), Utf8Path::new(expected));
+        assert_eq!(!resolved.warnings().is_empty(), outside);
+    }
+
+    #[parameterized(
+        sibling = { "toolchain", "C:/repo/toolchain", false },
+        windows_separators = { r"toolchains\..\stable", "C:/repo/stable", false },
+        outside = { r"..\..\toolchain", "C:/toolchain", true },
+    )]
+    fn resolve_windows_location(path: &str, expected: &str, outside: bool) {
+        let value = ToolchainPath {
+            path: PortablePath::from(path),
+        };
+
+        let resolved = value.resolve(r"C:\repo\rust-toolchain.toml").unwrap();
+        assert_eq!(resolved.path(), Utf8Path::new(expected));
+        assert_eq!(!resolved.warnings().is_empty(), outside);
+
+        let resolved = value
+            .resolve_within(r"C:\repo\rust-toolchain.toml", r"C:\repo")
+            .unwrap();
+        assert_eq!(resolved.path(), Utf8Path::new(expected));
+        assert_eq!(!resolved.warnings().is_empty(), outside);
+    }
+
+    #[test]
+    fn reject_relative_toolchain_file() {
+        let value = ToolchainPath {
+            path: PortablePath::from("../../toolchain"),
+        };
+
+        assert_eq!(
+            value.resolve("rust-toolchain.toml").unwrap_err(),
+            ResolveError::RelativeLocation(Utf8PathBuf::from("rust-toolchain.toml"))
+        );
+    }
+
+    #[test]
+    fn reject_relative_root() {
+        let value = ToolchainPath {
+            path: PortablePath::from("toolchain"),
+        };
+
+        assert_eq!(
+            value
+                .resolve_within("/repo/rust-toolchain.toml", "repo")
+                .unwrap_err(),
+            ResolveError::RelativeRoot(Utf8PathBuf::from("repo"))
+        );
     }
 }
 
@@ -387,3 +501,270 @@ mod parser {
         assert_eq!(parser_from_slice.content, expected);
     }
 }
+
+mod options {
+    use crate::options::Level;
+    use crate::path::Platform;
+    use crate::toml::{Parser, ParserError, ToolchainSection};
+    use crate::{ParserOptions, Warning};
+    use camino::Utf8Path;
+    use yare::parameterized;
+
+    const UNKNOWN_KEYS: &str = r#"[toolchain]
+channel = "stable"
+componets = ["rustfmt"]
+
+[other]
+key = 1
+"#;
+
+    const PATH_WITH_CHANNEL: &str = r#"[toolchain]
+path = "/path/to/local/toolchain"
+channel = "stable"
+"#;
+
+    const RELATIVE_PATH: &str = r#"[toolchain]
+path = "local/toolchain"
+"#;
+
+    #[test]
+    fn rustup_exact_ignores_unknown_keys() {
+        let parser = Parser::new(UNKNOWN_KEYS).with_options(ParserOptions::rustup_exact());
+
+        let (file, warnings) = parser.parse_with_warnings().unwrap();
+        assert_eq!(
+            file.toolchain().spec().unwrap().channel().unwrap().name(),
+            "stable"
+        );
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn warn_unknown_keys() {
+        let options = ParserOptions::rustup_exact().with_unknown_keys(Level::Warn);
+        let parser = Parser::new(UNKNOWN_KEYS).with_options(options);
+
+        let (_, warnings) = parser.parse_with_warnings().unwrap();
+        assert_eq!(
+            warnings,
+            vec![
+                Warning::UnknownKey("other".to_string()),
+                Warning::UnknownKey("toolchain.componets".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn pedantic_rejects_unknown_keys() {
+        let parser = Parser::new(UNKNOWN_KEYS).with_options(ParserOptions::pedantic());
+
+        let result = parser.parse();
+        assert_eq!(
+            result.unwrap_err(),
+            ParserError::Denied(Warning::UnknownKey("other".to_string()))
+        );
+    }
+
+    #[parameterized(
+        rustup_exact = { ParserOptions::rustup_exact() },
+        pedantic = { ParserOptions::pedantic() },
+    )]
+    fn reject_path_with_channel(options: ParserOptions) {
+        let parser = Parser::new(PATH_WITH_CHANNEL).with_options(options);
+
+        let result = parser.parse();
+        assert_eq!(
+            result.unwrap_err(),
+            ParserError::Denied(Warning::PathWithSpecKeys)
+        );
+    }
+
+    #[test]
+    fn lenient_accepts_path_with_channel() {
+        let parser = Parser::new(PATH_WITH_CHANNEL).with_options(ParserOptions::lenient());
+
+        let result = parser.parse().unwrap();
+        assert_eq!(
+            result.toolchain().path().unwrap().path(),
+            Utf8Path::new("/path/to/local/toolchain")
+        );
+    }
+
+    #[test]
+    fn rustup_exact_accepts_relative_path() {
+        let parser = Parser::new(RELATIVE_PATH).with_options(ParserOptions::rustup_exact());
+
+        let result = parser.parse().unwrap();
+        assert!(matches!(result.toolchain(), ToolchainSection::Path(_)));
+    }
+
+    #[test]
+    fn pedantic_rejects_relative_path() {
+        let parser = Parser::new(RELATIVE_PATH).with_options(ParserOptions::pedantic());
+
+        let result = parser.parse();
+        assert_eq!(
+            result.unwrap_err(),
+            ParserError::Denied(Warning::RelativePath("local/toolchain".to_string()))
+        );
+    }
+
+    #[test]
+    fn platform_specific_path_warns() {
+        let options = ParserOptions::rustup_exact().with_platform_specific_paths(Level::Warn);
+        let parser = Parser::new(super::RUSTUP_BOOK_LOCAL_TOOLCHAIN).with_options(options);
+
+        let (_, warnings) = parser.parse_with_warnings().unwrap();
+        assert_eq!(
+            warnings,
+            vec![Warning::PlatformSpecificPath {
+                path: "/path/to/local/toolchain".to_string(),
+                platform: Platform::Posix,
+            }]
+        );
+    }
+
+    #[test]
+    fn pedantic_rejects_non_ascii() {
+        let content = "[toolchain]\nchannel = \"❤️\"\n";
+        let parser = Parser::new(content).with_options(ParserOptions::pedantic());
+
+        let result = parser.parse();
+        assert_eq!(result.unwrap_err(), ParserError::InvalidEncodingStrict);
+    }
+
+    #[test]
+    fn pedantic_accepts_rustup_book_layout() {
+        let parser = Parser::new(super::RUSTUP_BOOK_SPEC).with_options(ParserOptions::pedantic());
+
+        let (_, warnings) = parser.parse_with_warnings().unwrap();
+        assert!(warnings.is_empty());
+    }
+}
+
+mod limits {
+    use crate::options::Limits;
+    use crate::toml::{Key, Parser, ParserError};
+    use crate::ParserOptions;
+    use yare::parameterized;
+
+    fn parse(content: &str, limits: Limits) -> Result<crate::RustToolchainToml, ParserError> {
+        Parser::new(content)
+            .with_options(ParserOptions::default().with_limits(limits))
+            .parse()
+    }
+
+    #[test]
+    fn default_limits_accept_rustup_book_layout() {
+        assert!(parse(super::RUSTUP_BOOK_SPEC, Limits::default()).is_ok());
+    }
+
+    #[test]
+    fn too_large() {
+        let result = parse(
+            super::RUSTUP_BOOK_SPEC,
+            Limits::default().with_max_bytes(16),
+        );
+
+        assert_eq!(result.unwrap_err(), ParserError::TooLarge(16));
+    }
+
+    #[parameterized(
+        arrays = { "[toolchain]\ncomponents = [[[[\"rustfmt\"]]]]" },
+        inline_tables = { "toolchain = { channel = { a = { b = { c = 1 } } } }" },
+        unterminated = { "[[[[[[[[[[[[[[[[[[[[" },
+        dotted_header = { "[toolchain.a.b.c]\nkey = 1" },
+        dotted_array_of_tables = { "[[toolchain.a.b.c]]\nkey = 1" },
+        dotted_key = { "toolchain.a.b.c.d = 1" },
+        dotted_key_in_table = { "[toolchain.a]\nb.c.d = 1" },
+        dotted_key_in_inline_table = { "toolchain = { a = 1, b.c.d.e = 1 }" },
+        array_in_table = { "[toolchain.a]\nb = [[1]]" },
+    )]
+    fn too_deep(content: &str) {
+        let result = parse(content, Limits::default().with_max_nesting(3));
+
+        assert_eq!(result.unwrap_err(), ParserError::TooDeep(3));
+    }
+
+    #[parameterized(
+        strings = { "[toolchain]\nchannel = \"[[[[\"" },
+        literal_strings = { "[toolchain]\nchannel = '[[[['" },
+        multi_line_strings = { "[toolchain]\nchannel = \"\"\"\n[[[[\n\"\"\"" },
+        escaped_quote = { "[toolchain]\nchannel = \"\\\"[[[[\"" },
+        comments = { "[toolchain] # [[[[\nchannel = \"stable\"" },
+        quoted_keys = { "[toolchain]\n\"a.b.c\" = 1\n['x.y.z']\nkey = 1" },
+        floats = { "[toolchain]\nextra = [1.5, 2.5]\nother = { a = 1.5 }" },
+        next_table = { "[toolchain.a]\nkey = 1\n[other]\nb = 1" },
+    )]
+    fn nesting_ignores_strings_and_comments(content: &str) {
+        let result = parse(content, Limits::default().with_max_nesting(2));
+
+        assert!(result.is_ok());
+    }
+
+    #[parameterized(
+        components = { "[toolchain]\ncomponents = [\"a\", \"b\", \"c\"]", Key::Components },
+        targets = { "[toolchain]\ntargets = [\"a\", \"b\", \"c\"]", Key::Targets },
+    )]
+    fn too_many_items(content: &str, key: Key) {
+        let result = parse(content, Limits::default().with_max_items(2));
+
+        assert!(
+            matches!(result.unwrap_err(), ParserError::TooManyItems { key: k, limit: 2 } if k == key)
+        );
+    }
+
+    #[parameterized(
+        channel = { "[toolchain]\nchannel = \"nightly-2020-07-10\"", Key::Channel },
+        profile = { "[toolchain]\nprofile = \"complete-profile\"", Key::Profile },
+        path = { "[toolchain]\npath = \"/path/to/toolchain\"", Key::Path },
+        component = { "[toolchain]\ncomponents = [\"rustfmt\", \"rust-analyzer\"]", Key::Components },
+        target = { "[toolchain]\ntargets = [\"wasm32-unknown-unknown\"]", Key::Targets },
+    )]
+    fn string_too_long(content: &str, key: Key) {
+        let result = parse(content, Limits::default().with_max_string_len(8));
+
+        assert!(
+            matches!(result.unwrap_err(), ParserError::StringTooLong { key: k, limit: 8 } if k == key)
+        );
+    }
+
+    #[test]
+    fn recovering_does_not_scan_too_large_content() {
+        let options = ParserOptions::default().with_limits(Limits::default().with_max_bytes(16));
+        let recovered = Parser::new(super::RUSTUP_BOOK_SPEC)
+            .with_options(options)
+            .parse_recovering();
+
+        assert!(recovered.toolchain().is_none());
+        assert_eq!(recovered.diagnostics().len(), 1);
+    }
+}
+
+mod toml_error {
+    use crate::toml::{Parser, ParserError};
+
+    #[test]
+    fn syntax_error_has_span() {
+        let result = Parser::new("[toolchain]\nchannel = nightly").parse();
+
+        let ParserError::TomlParse(error) = result.unwrap_err() else {
+            panic!("expected a TOML parse error");
+        };
+
+        assert_eq!(error.span(), Some(22..23));
+        assert!(!error.message().is_empty());
+        assert_eq!(error.to_string(), error.message());
+    }
+
+    #[test]
+    fn invalid_utf8_has_no_span() {
+        let result = Parser::from_slice(b"[toolchain]\nchannel = \"\xFF\"").parse();
+
+        let ParserError::TomlParse(error) = result.unwrap_err() else {
+            panic!("expected a TOML parse error");
+        };
+
+        assert_eq!(error.span(), None);
+    }
+}
@@ -0,0 +1,63 @@
+use crate::legacy::GrammarViolation;
+use crate::path::Platform;
+use crate::rustup::{Feature, RustupVersion};
+use alloc::string::String;
+use core::fmt;
+
+/// A questionable finding, which does not necessarily prevent a toolchain file from being parsed.
+///
+/// Whether a finding is ignored, collected as a warning, or rejected as an error, is determined
+/// by the [`ParserOptions`](crate::ParserOptions).
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "kind", content = "value", rename_all = "snake_case")]
+pub enum Warning {
+    UnknownKey(String),
+    SurroundingWhitespace,
+    RelativePath(String),
+    PlatformSpecificPath {
+        path: String,
+        platform: Platform,
+    },
+    PathOutsideRoot(String),
+    PathWithSpecKeys,
+    TomlReadAsLegacy,
+    LegacyGrammar(GrammarViolation),
+    UnsupportedFeature {
+        feature: Feature,
+        version: RustupVersion,
+    },
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownKey(key) => write!(f, "Found unknown key '{key}'"),
+            Self::SurroundingWhitespace => {
+                f.write_str("Found whitespace surrounding the toolchain file contents")
+            }
+            Self::RelativePath(path) => write!(f, "Found relative toolchain path '{path}'"),
+            Self::PlatformSpecificPath { path, platform } => {
+                write!(f, "Found toolchain path '{path}', which can only be used on {platform}")
+            }
+            Self::PathOutsideRoot(path) => {
+                write!(f, "Found toolchain path '{path}', which leaves the repository root")
+            }
+            Self::PathWithSpecKeys => f.write_str(
+                "Found toolchain path combined with a channel, components, targets or profile",
+            ),
+            Self::TomlReadAsLegacy => f.write_str("Content which looks like TOML was read as a legacy toolchain file, because it failed to parse as TOML"),
+            Self::LegacyGrammar(violation) => write!(
+                f,
+                "Found a legacy toolchain file which is not a valid toolchain name or path: {violation}"
+            ),
+            Self::UnsupportedFeature { feature, version } => write!(
+                f,
+                "Found {feature}, which requires rustup {}, but rustup {version} was selected",
+                feature.since()
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Warning {}